@@ -1,10 +1,12 @@
 use cumulus_primitives_core::ParaId;
-use parachain_template_runtime::{AccountId, AuraId, Signature, EXISTENTIAL_DEPOSIT};
+use parachain_template_runtime::{
+	AccountId, AuraId, Signature, TreasuryAccount, EXISTENTIAL_DEPOSIT,
+};
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
 use sp_core::{sr25519, Pair, Public};
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::traits::{Get, IdentifyAccount, Verify};
 
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type ChainSpec =
@@ -61,6 +63,18 @@ pub fn template_session_keys(keys: AuraId) -> parachain_template_runtime::Sessio
 	parachain_template_runtime::SessionKeys { aura: keys }
 }
 
+/// The initial state of the template pallet: `Something` is set to 42, and Alice and Bob each
+/// have a value stored with its deposit held.
+fn template_genesis_values() -> parachain_template_runtime::TemplatePalletConfig {
+	parachain_template_runtime::TemplatePalletConfig {
+		something: Some(42),
+		account_values: vec![
+			(get_account_id_from_seed::<sr25519::Public>("Alice"), 1),
+			(get_account_id_from_seed::<sr25519::Public>("Bob"), 2),
+		],
+	}
+}
+
 pub fn development_config() -> ChainSpec {
 	// Give your base currency a unit name and decimal places
 	let mut properties = sc_chain_spec::Properties::new();
@@ -101,8 +115,14 @@ pub fn development_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				1000.into(),
+				template_genesis_values(),
+				// initial council members.
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 			)
 		},
 		Vec::new(),
@@ -157,8 +177,14 @@ pub fn local_testnet_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
 				1000.into(),
+				template_genesis_values(),
+				// initial council members.
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+					get_account_id_from_seed::<sr25519::Public>("Charlie"),
+				],
 			)
 		},
 		// Bootnodes
@@ -182,8 +208,9 @@ pub fn local_testnet_config() -> ChainSpec {
 fn testnet_genesis(
 	invulnerables: Vec<(AccountId, AuraId)>,
 	endowed_accounts: Vec<AccountId>,
-	root: AccountId,
 	id: ParaId,
+	template_pallet: parachain_template_runtime::TemplatePalletConfig,
+	council_members: Vec<AccountId>,
 ) -> parachain_template_runtime::RuntimeGenesisConfig {
 	parachain_template_runtime::RuntimeGenesisConfig {
 		system: parachain_template_runtime::SystemConfig {
@@ -193,8 +220,16 @@ fn testnet_genesis(
 			..Default::default()
 		},
 		balances: parachain_template_runtime::BalancesConfig {
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+			// The treasury is funded from genesis, like any endowed account.
+			balances: endowed_accounts
+				.iter()
+				.cloned()
+				.chain(Some(TreasuryAccount::get()))
+				.map(|k| (k, 1 << 60))
+				.collect(),
 		},
+		assets: Default::default(),
+		foreign_assets: Default::default(),
 		parachain_info: parachain_template_runtime::ParachainInfoConfig {
 			parachain_id: id,
 			..Default::default()
@@ -226,6 +261,16 @@ fn testnet_genesis(
 			..Default::default()
 		},
 		transaction_payment: Default::default(),
-		sudo: parachain_template_runtime::SudoConfig { key: Some(root) },
+		// Council members are managed through `CouncilMembership`, which initializes `Council`.
+		council: Default::default(),
+		council_membership: parachain_template_runtime::CouncilMembershipConfig {
+			members: council_members
+				.try_into()
+				.expect("the initial council fits within `CouncilMaxMembers`; qed"),
+			..Default::default()
+		},
+		treasury: Default::default(),
+		inbound_xcm: Default::default(),
+		template_pallet,
 	}
 }
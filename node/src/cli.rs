@@ -54,6 +54,10 @@ const AFTER_HELP_EXAMPLE: &str = color_print::cstr!(
            Launch a full node with default parachain <italic>local-testnet</> and relay chain <italic>rococo-local</>.
    <bold>parachain-template-node --collator</>
            Launch a collator with default parachain <italic>local-testnet</> and relay chain <italic>rococo-local</>.
+   <bold>parachain-template-node --collator --relay-chain-rpc-url ws://relay-0:9944 --relay-chain-rpc-url ws://relay-1:9944 -- --chain rococo-local</>
+           Launch a collator that follows the relay chain with a minimal node reading from the first reachable endpoint, failing over to the next.
+   <bold>parachain-template-node --dev --instant-seal</>
+           Launch a standalone development node that authors a block for every transaction, without a relay chain.
  "#
 );
 #[derive(Debug, clap::Parser)]
@@ -80,11 +84,44 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Author a block for every transaction received, without a relay chain.
+	///
+	/// The parachain inherent is mocked, and blocks can also be authored and finalized through
+	/// the `engine_createBlock` and `engine_finalizeBlock` RPCs. Only for development chains.
+	#[arg(long, conflicts_with = "dev_block_time")]
+	pub instant_seal: bool,
+
+	/// Author a block every given number of milliseconds, without a relay chain.
+	///
+	/// Like `--instant-seal`, but blocks are authored at a fixed interval, even when empty.
+	#[arg(long, value_name = "MILLISECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+	pub dev_block_time: Option<u64>,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
 }
 
+impl Cli {
+	/// How blocks are authored when running standalone, if requested.
+	pub fn sealing(&self) -> Option<Sealing> {
+		match (self.instant_seal, self.dev_block_time) {
+			(true, _) => Some(Sealing::Instant),
+			(false, Some(millis)) => Some(Sealing::Interval(millis)),
+			(false, None) => None,
+		}
+	}
+}
+
+/// How a standalone development node authors blocks.
+#[derive(Debug, Copy, Clone)]
+pub enum Sealing {
+	/// A block for every transaction received.
+	Instant,
+	/// A block every given number of milliseconds.
+	Interval(u64),
+}
+
 #[derive(Debug)]
 pub struct RelayChainCli {
 	/// The actual relay chain cli object.
@@ -113,3 +150,58 @@ impl RelayChainCli {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[test]
+	fn relay_chain_rpc_urls_are_kept_in_order() {
+		let cli = Cli::try_parse_from([
+			"parachain-template-node",
+			"--collator",
+			"--relay-chain-rpc-url",
+			"ws://relay-0:9944",
+			"--relay-chain-rpc-url",
+			"wss://relay-1:443",
+		])
+		.unwrap();
+
+		let urls = cli.run.collator_options().relay_chain_rpc_urls;
+		assert_eq!(
+			urls.iter().map(ToString::to_string).collect::<Vec<_>>(),
+			vec!["ws://relay-0:9944/", "wss://relay-1:443/"]
+		);
+	}
+
+	#[test]
+	fn relay_chain_rpc_urls_must_be_websockets() {
+		assert!(Cli::try_parse_from([
+			"parachain-template-node",
+			"--relay-chain-rpc-url",
+			"http://relay-0:9933",
+		])
+		.is_err());
+	}
+
+	#[test]
+	fn instant_seal_conflicts_with_dev_block_time() {
+		assert!(Cli::try_parse_from([
+			"parachain-template-node",
+			"--instant-seal",
+			"--dev-block-time",
+			"6000",
+		])
+		.is_err());
+
+		let cli =
+			Cli::try_parse_from(["parachain-template-node", "--dev-block-time", "6000"]).unwrap();
+		assert!(matches!(cli.sealing(), Some(Sealing::Interval(6000))));
+	}
+
+	#[test]
+	fn dev_block_time_must_be_positive() {
+		assert!(Cli::try_parse_from(["parachain-template-node", "--dev-block-time", "0"]).is_err());
+	}
+}
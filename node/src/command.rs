@@ -8,7 +8,10 @@ use sc_cli::{
 	ChainSpec, CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams,
 	NetworkParams, Result, SharedParams, SubstrateCli,
 };
-use sc_service::config::{BasePath, PrometheusConfig};
+use sc_service::{
+	config::{BasePath, PrometheusConfig},
+	ChainType,
+};
 use sp_runtime::traits::AccountIdConversion;
 
 use crate::{
@@ -258,6 +261,20 @@ pub fn run() -> Result<()> {
 					.map(|e| e.para_id)
 					.ok_or("Could not find parachain ID in chain-spec.")?;
 
+				if let Some(sealing) = cli.sealing() {
+					if config.chain_spec.chain_type() != ChainType::Development {
+						return Err("--instant-seal and --dev-block-time are only available on \
+							development chains, such as --dev."
+							.into())
+					}
+
+					info!("Authoring blocks without a relay chain: {:?}", sealing);
+
+					return crate::service::start_dev_node(config, sealing, ParaId::from(para_id))
+						.map(|r| r.0)
+						.map_err(Into::into)
+				}
+
 				let polkadot_cli = RelayChainCli::new(
 					&config,
 					[RelayChainCli::executable_name()].iter().chain(cli.relay_chain_args.iter()),
@@ -277,6 +294,14 @@ pub fn run() -> Result<()> {
 
 				info!("Parachain Account: {parachain_account}");
 				info!("Is collating: {}", if config.role.is_authority() { "yes" } else { "no" });
+				if collator_options.relay_chain_rpc_urls.is_empty() {
+					info!("Relay chain: embedded full node");
+				} else {
+					info!(
+						"Relay chain: minimal node over RPC, failing over across {} endpoint(s)",
+						collator_options.relay_chain_rpc_urls.len()
+					);
+				}
 
 				if !collator_options.relay_chain_rpc_urls.is_empty() &&
 					!cli.relay_chain_args.is_empty()
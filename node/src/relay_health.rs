@@ -0,0 +1,324 @@
+//! Health reporting of the relay chain RPC endpoints.
+//!
+//! With `--relay-chain-rpc-url`, the collator follows the relay chain with a minimal node that
+//! reads it through the given endpoints, its RPC client failing over to the next one in the given
+//! order when the one in use goes away. This task probes every endpoint at an interval, over a
+//! connection of its own kept open between probes, and reports whether each is reachable, and its
+//! best block, in the logs and as Prometheus metrics.
+//!
+//! Which endpoint the RPC client is using is not reported, as cumulus' client does not expose it.
+
+use std::{sync::Arc, time::Duration};
+
+use cumulus_relay_chain_interface::RelayChainInterface;
+use jsonrpsee::{
+	core::{client::ClientT, rpc_params},
+	ws_client::{WsClient, WsClientBuilder},
+};
+use polkadot_primitives::{BlockId, BlockNumber, Header};
+use substrate_prometheus_endpoint::{
+	register, Gauge, GaugeVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// How often the endpoints are probed.
+const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long an endpoint has to answer a probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A relay chain RPC endpoint, and the client probing it.
+struct Endpoint {
+	url: String,
+	/// Dropped when a probe fails, and connected again at the next one.
+	client: Option<WsClient>,
+}
+
+impl Endpoint {
+	fn new(url: String) -> Self {
+		Self { url, client: None }
+	}
+
+	/// The best block of the endpoint, if it is reachable.
+	async fn probe(&mut self) -> Option<BlockNumber> {
+		if !self.client.as_ref().map_or(false, WsClient::is_connected) {
+			self.client = WsClientBuilder::default()
+				.connection_timeout(PROBE_TIMEOUT)
+				.request_timeout(PROBE_TIMEOUT)
+				.build(&self.url)
+				.await
+				.ok();
+		}
+		let client = self.client.as_ref()?;
+		match client.request::<Option<Header>, _>("chain_getHeader", rpc_params![]).await {
+			Ok(header) => header.map(|header| header.number),
+			Err(_) => {
+				self.client = None;
+				None
+			},
+		}
+	}
+}
+
+/// Prometheus metrics of the relay chain RPC endpoints.
+#[derive(Clone)]
+struct Metrics {
+	endpoint_up: GaugeVec<U64>,
+	endpoint_best_block: GaugeVec<U64>,
+	best_block: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			endpoint_up: register(
+				GaugeVec::new(
+					Opts::new(
+						"parachain_relay_chain_rpc_endpoint_up",
+						"Whether the relay chain RPC endpoint answered the last probe",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+			endpoint_best_block: register(
+				GaugeVec::new(
+					Opts::new(
+						"parachain_relay_chain_rpc_endpoint_best_block",
+						"Best relay chain block of the relay chain RPC endpoint at the last probe",
+					),
+					&["endpoint"],
+				)?,
+				registry,
+			)?,
+			best_block: register(
+				Gauge::new(
+					"parachain_relay_chain_best_block",
+					"Best relay chain block seen by the collator",
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Report the result of a probe of `endpoint`. An unreachable endpoint has no best block,
+	/// rather than the one it had when it was last reachable.
+	fn report_endpoint(&self, endpoint: &str, best_block: Option<BlockNumber>) {
+		self.endpoint_up.with_label_values(&[endpoint]).set(best_block.is_some() as u64);
+		match best_block {
+			Some(number) =>
+				self.endpoint_best_block.with_label_values(&[endpoint]).set(number.into()),
+			None => {
+				let _ = self.endpoint_best_block.remove_label_values(&[endpoint]);
+			},
+		}
+	}
+}
+
+/// Probe `endpoints` all at once, returning their best blocks in the order given.
+async fn probe_all(endpoints: &mut [Endpoint]) -> Vec<Option<BlockNumber>> {
+	futures::future::join_all(endpoints.iter_mut().map(Endpoint::probe)).await
+}
+
+/// The best relay chain block seen by the collator.
+async fn best_block(relay_chain_interface: &Arc<dyn RelayChainInterface>) -> Option<BlockNumber> {
+	let hash = relay_chain_interface.best_block_hash().await.ok()?;
+	let header = relay_chain_interface.header(BlockId::Hash(hash)).await.ok()??;
+	Some(header.number)
+}
+
+/// Probe `endpoints` every [`PROBE_INTERVAL`], and report their health until the node stops.
+pub async fn run(
+	endpoints: Vec<String>,
+	relay_chain_interface: Arc<dyn RelayChainInterface>,
+	prometheus_registry: Option<Registry>,
+) {
+	let metrics = prometheus_registry.as_ref().and_then(|registry| {
+		Metrics::register(registry)
+			.map_err(|e| log::warn!("Failed to register relay chain RPC endpoint metrics: {e}"))
+			.ok()
+	});
+	let mut endpoints: Vec<_> = endpoints.into_iter().map(Endpoint::new).collect();
+
+	loop {
+		let best_blocks = probe_all(&mut endpoints).await;
+
+		for (endpoint, best_block) in endpoints.iter().zip(&best_blocks) {
+			if best_block.is_none() {
+				log::warn!("Relay chain RPC endpoint {} is unreachable.", endpoint.url);
+			}
+			if let Some(ref metrics) = metrics {
+				metrics.report_endpoint(&endpoint.url, *best_block);
+			}
+		}
+		if best_blocks.iter().all(Option::is_none) {
+			log::warn!("⚠️  None of the relay chain RPC endpoints is reachable.");
+		}
+		if let Some(ref metrics) = metrics {
+			if let Some(number) = best_block(&relay_chain_interface).await {
+				metrics.best_block.set(number.into());
+			}
+		}
+
+		futures_timer::Delay::new(PROBE_INTERVAL).await;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use cumulus_relay_chain_rpc_interface::{create_client_and_start_worker, RelayChainRpcClient};
+	use jsonrpsee::{
+		core::{RpcResult, StringError},
+		server::{ServerBuilder, ServerHandle},
+		RpcModule,
+	};
+	use sc_service::TaskManager;
+	use std::net::SocketAddr;
+
+	fn localhost() -> SocketAddr {
+		"127.0.0.1:0".parse().unwrap()
+	}
+
+	/// Serve a relay chain whose best block is `number` on `addr`: its best header, and the
+	/// subscriptions to new heads cumulus' RPC client opens as it connects.
+	async fn serve(addr: SocketAddr, number: BlockNumber) -> (String, ServerHandle) {
+		let server = ServerBuilder::default().build(addr).await.unwrap();
+		let url = format!("ws://{}", server.local_addr().unwrap());
+		let header = Header {
+			number,
+			parent_hash: Default::default(),
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		};
+
+		let mut module = RpcModule::new(());
+		module
+			.register_method("chain_getHeader", move |_, _| RpcResult::Ok(Some(header.clone())))
+			.unwrap();
+		for (subscribe, notification, unsubscribe) in [
+			("chain_subscribeAllHeads", "chain_allHead", "chain_unsubscribeAllHeads"),
+			("chain_subscribeNewHeads", "chain_newHead", "chain_unsubscribeNewHeads"),
+			(
+				"chain_subscribeFinalizedHeads",
+				"chain_finalizedHead",
+				"chain_unsubscribeFinalizedHeads",
+			),
+		] {
+			module
+				.register_subscription(
+					subscribe,
+					notification,
+					unsubscribe,
+					|_, pending, _| async {
+						let sink = pending.accept().await?;
+						sink.closed().await;
+						Ok::<_, StringError>(())
+					},
+				)
+				.unwrap();
+		}
+		(url, server.start(module))
+	}
+
+	async fn stop(server: ServerHandle) {
+		server.stop().unwrap();
+		server.stopped().await;
+	}
+
+	/// The URL of an endpoint nothing answers on.
+	async fn unreachable() -> String {
+		let (url, server) = serve(localhost(), 0).await;
+		stop(server).await;
+		url
+	}
+
+	/// A client of the relay chain RPC interface over `urls`, given as on the command line.
+	async fn relay_chain_client(
+		urls: &[&str],
+		task_manager: &mut TaskManager,
+	) -> RelayChainRpcClient {
+		let args = ["parachain-template-node"]
+			.into_iter()
+			.chain(urls.iter().flat_map(|url| ["--relay-chain-rpc-url", *url]));
+		let cli = crate::cli::Cli::try_parse_from(args).unwrap();
+		create_client_and_start_worker(
+			cli.run.collator_options().relay_chain_rpc_urls,
+			task_manager,
+		)
+		.await
+		.unwrap()
+	}
+
+	async fn client_best_block(client: &RelayChainRpcClient) -> Option<BlockNumber> {
+		client.chain_get_header(None).await.unwrap().map(|header| header.number)
+	}
+
+	/// The number of samples of the metric `name` in `registry`.
+	fn samples(registry: &Registry, name: &str) -> usize {
+		registry
+			.gather()
+			.iter()
+			.find(|family| family.get_name() == name)
+			.map_or(0, |family| family.get_metric().len())
+	}
+
+	#[test]
+	fn unreachable_endpoint_has_no_best_block() {
+		let registry = Registry::new();
+		let metrics = Metrics::register(&registry).unwrap();
+
+		metrics.report_endpoint("ws://relay-0:9944", Some(10));
+		metrics.report_endpoint("ws://relay-1:9944", Some(10));
+		assert_eq!(samples(&registry, "parachain_relay_chain_rpc_endpoint_best_block"), 2);
+
+		metrics.report_endpoint("ws://relay-0:9944", None);
+		assert_eq!(samples(&registry, "parachain_relay_chain_rpc_endpoint_best_block"), 1);
+		assert_eq!(metrics.endpoint_up.with_label_values(&["ws://relay-0:9944"]).get(), 0);
+		assert_eq!(metrics.endpoint_up.with_label_values(&["ws://relay-1:9944"]).get(), 1);
+
+		metrics.report_endpoint("ws://relay-0:9944", Some(12));
+		assert_eq!(metrics.endpoint_best_block.with_label_values(&["ws://relay-0:9944"]).get(), 12);
+	}
+
+	#[tokio::test]
+	async fn probes_report_every_endpoint_in_the_order_given() {
+		let (first, _first) = serve(localhost(), 5).await;
+		let (third, _third) = serve(localhost(), 7).await;
+		let mut endpoints: Vec<_> =
+			[first, unreachable().await, third].into_iter().map(Endpoint::new).collect();
+
+		assert_eq!(probe_all(&mut endpoints).await, vec![Some(5), None, Some(7)]);
+	}
+
+	#[tokio::test]
+	async fn probes_reconnect_to_an_endpoint_that_comes_back() {
+		let (url, server) = serve(localhost(), 5).await;
+		let mut endpoints = vec![Endpoint::new(url.clone())];
+		assert_eq!(probe_all(&mut endpoints).await, vec![Some(5)]);
+
+		stop(server).await;
+		assert_eq!(probe_all(&mut endpoints).await, vec![None]);
+		assert!(endpoints[0].client.is_none());
+
+		let (_, _server) = serve(url.trim_start_matches("ws://").parse().unwrap(), 6).await;
+		assert_eq!(probe_all(&mut endpoints).await, vec![Some(6)]);
+	}
+
+	#[tokio::test]
+	async fn relay_chain_client_fails_over_in_the_order_given() {
+		let mut task_manager = TaskManager::new(tokio::runtime::Handle::current(), None).unwrap();
+		let unreachable = unreachable().await;
+		let (first, first_server) = serve(localhost(), 5).await;
+		let (second, _second_server) = serve(localhost(), 7).await;
+
+		// The client skips the unreachable endpoint, and uses the next one in the order given.
+		let client = relay_chain_client(&[&unreachable, &first, &second], &mut task_manager).await;
+		assert_eq!(client_best_block(&client).await, Some(5));
+
+		// Once that endpoint goes away, it moves on to the one after it.
+		stop(first_server).await;
+		assert_eq!(client_best_block(&client).await, Some(7));
+	}
+}
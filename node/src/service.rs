@@ -4,8 +4,11 @@
 use std::{sync::Arc, time::Duration};
 
 use cumulus_client_cli::CollatorOptions;
+use futures::{channel::mpsc, FutureExt, SinkExt, StreamExt};
 // Local Runtime Types
-use parachain_template_runtime::{opaque::Block, RuntimeApi};
+use parachain_template_runtime::{opaque::Block, pallet_parachain_template, Hash, RuntimeApi};
+
+use crate::cli::Sealing;
 
 // Cumulus Imports
 use cumulus_client_consensus_aura::{AuraConsensus, BuildAuraConsensusParams, SlotProportion};
@@ -17,21 +20,33 @@ use cumulus_client_service::{
 	start_full_node, BuildNetworkParams, StartCollatorParams, StartFullNodeParams,
 };
 use cumulus_primitives_core::ParaId;
+use cumulus_primitives_parachain_inherent::{
+	MockValidationDataInherentDataProvider, MockXcmConfig,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 // Substrate Imports
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use sc_client_api::Backend;
-use sc_consensus::ImportQueue;
+use sc_consensus::{ImportQueue, LongestChain};
+use sc_consensus_manual_seal::{
+	consensus::aura::AuraConsensusDataProvider, run_manual_seal, EngineCommand, ManualSealParams,
+};
 use sc_executor::{
 	HeapAllocStrategy, NativeElseWasmExecutor, WasmExecutor, DEFAULT_HEAP_ALLOC_STRATEGY,
 };
-use sc_network::NetworkBlock;
+use sc_network::{NetworkBlock, NetworkService};
 use sc_network_sync::SyncingService;
-use sc_service::{Configuration, PartialComponents, TFullBackend, TFullClient, TaskManager};
+use sc_service::{
+	ChainType, Configuration, KeystoreContainer, PartialComponents, TFullBackend, TFullClient,
+	TaskManager,
+};
 use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker, TelemetryWorkerHandle};
-use sc_transaction_pool_api::OffchainTransactionPoolFactory;
-use sp_keystore::KeystorePtr;
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{digests::CompatibleDigestItem, sr25519::AuthoritySignature};
+use sp_keystore::{Keystore, KeystorePtr};
+use sp_runtime::traits::Header as HeaderT;
 use substrate_prometheus_endpoint::Registry;
 
 /// Native executor type.
@@ -57,23 +72,50 @@ type ParachainBackend = TFullBackend<Block>;
 
 type ParachainBlockImport = TParachainBlockImport<Block, Arc<ParachainClient>, ParachainBackend>;
 
+type Service = PartialComponents<
+	ParachainClient,
+	ParachainBackend,
+	(),
+	sc_consensus::DefaultImportQueue<Block, ParachainClient>,
+	sc_transaction_pool::FullPool<Block, ParachainClient>,
+	(ParachainBlockImport, Option<Telemetry>, Option<TelemetryWorkerHandle>),
+>;
+
 /// Starts a `ServiceBuilder` for a full service.
 ///
 /// Use this macro if you don't actually need the full service, but just the builder in order to
 /// be able to perform chain operations.
-pub fn new_partial(
+pub fn new_partial(config: &Configuration) -> Result<Service, sc_service::Error> {
+	new_partial_with(config, build_import_queue)
+}
+
+/// Like [`new_partial`], but importing blocks authored with manual seal.
+fn new_dev_partial(config: &Configuration) -> Result<Service, sc_service::Error> {
+	new_partial_with(config, |client, _, config, _, task_manager| {
+		Ok(sc_consensus_manual_seal::import_queue(
+			Box::new(client),
+			&task_manager.spawn_essential_handle(),
+			config.prometheus_registry(),
+		))
+	})
+}
+
+fn new_partial_with<BIQ>(
 	config: &Configuration,
-) -> Result<
-	PartialComponents<
-		ParachainClient,
-		ParachainBackend,
-		(),
+	build_import_queue: BIQ,
+) -> Result<Service, sc_service::Error>
+where
+	BIQ: FnOnce(
+		Arc<ParachainClient>,
+		ParachainBlockImport,
+		&Configuration,
+		Option<TelemetryHandle>,
+		&TaskManager,
+	) -> Result<
 		sc_consensus::DefaultImportQueue<Block, ParachainClient>,
-		sc_transaction_pool::FullPool<Block, ParachainClient>,
-		(ParachainBlockImport, Option<Telemetry>, Option<TelemetryWorkerHandle>),
+		sc_service::Error,
 	>,
-	sc_service::Error,
-> {
+{
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -182,6 +224,18 @@ async fn start_node_impl(
 	let transaction_pool = params.transaction_pool.clone();
 	let import_queue_service = params.import_queue.service();
 
+	if !collator_options.relay_chain_rpc_urls.is_empty() {
+		task_manager.spawn_handle().spawn(
+			"relay-chain-rpc-health",
+			None,
+			crate::relay_health::run(
+				collator_options.relay_chain_rpc_urls.iter().map(ToString::to_string).collect(),
+				relay_chain_interface.clone(),
+				prometheus_registry.clone(),
+			),
+		);
+	}
+
 	let (network, system_rpc_tx, tx_handler_controller, start_network, sync_service) =
 		build_network(BuildNetworkParams {
 			parachain_config: &parachain_config,
@@ -196,26 +250,15 @@ async fn start_node_impl(
 		.await?;
 
 	if parachain_config.offchain_worker.enabled {
-		use futures::FutureExt;
-
-		task_manager.spawn_handle().spawn(
-			"offchain-workers-runner",
-			"offchain-work",
-			sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
-				runtime_api_provider: client.clone(),
-				keystore: Some(params.keystore_container.keystore()),
-				offchain_db: backend.offchain_storage(),
-				transaction_pool: Some(OffchainTransactionPoolFactory::new(
-					transaction_pool.clone(),
-				)),
-				network_provider: network.clone(),
-				is_validator: parachain_config.role.is_authority(),
-				enable_http_requests: false,
-				custom_extensions: move |_| vec![],
-			})
-			.run(client.clone(), task_manager.spawn_handle())
-			.boxed(),
-		);
+		start_offchain_workers(
+			&parachain_config,
+			client.clone(),
+			&backend,
+			&params.keystore_container,
+			transaction_pool.clone(),
+			network.clone(),
+			&task_manager,
+		)?;
 	}
 
 	let rpc_builder = {
@@ -227,6 +270,7 @@ async fn start_node_impl(
 				client: client.clone(),
 				pool: transaction_pool.clone(),
 				deny_unsafe,
+				command_sink: None,
 			};
 
 			crate::rpc::create_full(deps).map_err(Into::into)
@@ -334,6 +378,45 @@ async fn start_node_impl(
 	Ok((task_manager, client))
 }
 
+/// Start the offchain workers, on development chains with Alice's key for the template pallet.
+fn start_offchain_workers(
+	config: &Configuration,
+	client: Arc<ParachainClient>,
+	backend: &Arc<ParachainBackend>,
+	keystore_container: &KeystoreContainer,
+	transaction_pool: Arc<sc_transaction_pool::FullPool<Block, ParachainClient>>,
+	network: Arc<NetworkService<Block, Hash>>,
+	task_manager: &TaskManager,
+) -> Result<(), sc_service::Error> {
+	// On development chains, give the template pallet's offchain worker Alice's key so that
+	// it can sign its transactions without any manual setup.
+	if config.chain_spec.chain_type() == ChainType::Development {
+		keystore_container
+			.keystore()
+			.sr25519_generate_new(pallet_parachain_template::KEY_TYPE, Some("//Alice"))
+			.map_err(|e| sc_service::Error::Application(Box::new(e)))?;
+	}
+
+	task_manager.spawn_handle().spawn(
+		"offchain-workers-runner",
+		"offchain-work",
+		sc_offchain::OffchainWorkers::new(sc_offchain::OffchainWorkerOptions {
+			runtime_api_provider: client.clone(),
+			keystore: Some(keystore_container.keystore()),
+			offchain_db: backend.offchain_storage(),
+			transaction_pool: Some(OffchainTransactionPoolFactory::new(transaction_pool)),
+			network_provider: network,
+			is_validator: config.role.is_authority(),
+			enable_http_requests: false,
+			custom_extensions: move |_| vec![],
+		})
+		.run(client, task_manager.spawn_handle())
+		.boxed(),
+	);
+
+	Ok(())
+}
+
 /// Build the import queue for the parachain runtime.
 fn build_import_queue(
 	client: Arc<ParachainClient>,
@@ -451,3 +534,180 @@ pub async fn start_parachain_node(
 ) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
 	start_node_impl(parachain_config, polkadot_config, collator_options, para_id, hwbench).await
 }
+
+/// Start a standalone development node, authoring blocks with manual seal instead of collating
+/// for a relay chain.
+///
+/// The parachain inherent is mocked, with a relay chain that advances two blocks per parachain
+/// block and never sends or receives messages. Each block is in the current slot of the wall
+/// clock, or in the slot after its parent if that is later, and its timestamp is the start of its
+/// slot. Blocks sealed further apart than the runtime's slot duration follow the wall clock, and
+/// those sealed faster run ahead of it, one slot per block, until the sealing slows down.
+pub fn start_dev_node(
+	config: Configuration,
+	sealing: Sealing,
+	para_id: ParaId,
+) -> sc_service::error::Result<(TaskManager, Arc<ParachainClient>)> {
+	let params = new_dev_partial(&config)?;
+	let (_, mut telemetry, _) = params.other;
+	let net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
+
+	let client = params.client.clone();
+	let backend = params.backend.clone();
+	let mut task_manager = params.task_manager;
+	let prometheus_registry = config.prometheus_registry().cloned();
+	let transaction_pool = params.transaction_pool.clone();
+
+	let (network, system_rpc_tx, tx_handler_controller, start_network, sync_service) =
+		sc_service::build_network(sc_service::BuildNetworkParams {
+			config: &config,
+			net_config,
+			client: client.clone(),
+			transaction_pool: transaction_pool.clone(),
+			spawn_handle: task_manager.spawn_handle(),
+			import_queue: params.import_queue,
+			block_announce_validator_builder: None,
+			warp_sync_params: None,
+		})?;
+
+	if config.offchain_worker.enabled {
+		start_offchain_workers(
+			&config,
+			client.clone(),
+			&backend,
+			&params.keystore_container,
+			transaction_pool.clone(),
+			network.clone(),
+			&task_manager,
+		)?;
+	}
+
+	// Blocks are authored on the commands of the manual seal RPCs and of the sealing task.
+	let (command_sink, commands_stream) = mpsc::channel::<EngineCommand<Hash>>(1024);
+	let seal = |create_empty| EngineCommand::SealNewBlock {
+		create_empty,
+		finalize: true,
+		parent_hash: None,
+		sender: None,
+	};
+	let mut sealing_sink = command_sink.clone();
+	let sealing_task = match sealing {
+		Sealing::Instant => {
+			let mut imported = transaction_pool.import_notification_stream();
+			async move {
+				while imported.next().await.is_some() {
+					if sealing_sink.send(seal(false)).await.is_err() {
+						break
+					}
+				}
+			}
+			.boxed()
+		},
+		Sealing::Interval(millis) => async move {
+			loop {
+				futures_timer::Delay::new(Duration::from_millis(millis)).await;
+				if sealing_sink.send(seal(true)).await.is_err() {
+					break
+				}
+			}
+		}
+		.boxed(),
+	};
+	task_manager.spawn_essential_handle().spawn("dev-sealing", None, sealing_task);
+
+	let rpc_builder = {
+		let client = client.clone();
+		let transaction_pool = transaction_pool.clone();
+
+		Box::new(move |deny_unsafe, _| {
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: transaction_pool.clone(),
+				deny_unsafe,
+				command_sink: Some(command_sink.clone()),
+			};
+
+			crate::rpc::create_full(deps).map_err(Into::into)
+		})
+	};
+
+	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+		rpc_builder,
+		client: client.clone(),
+		transaction_pool: transaction_pool.clone(),
+		task_manager: &mut task_manager,
+		config,
+		keystore: params.keystore_container.keystore(),
+		backend: backend.clone(),
+		network,
+		sync_service,
+		system_rpc_tx,
+		tx_handler_controller,
+		telemetry: telemetry.as_mut(),
+	})?;
+
+	let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+		task_manager.spawn_handle(),
+		client.clone(),
+		transaction_pool.clone(),
+		prometheus_registry.as_ref(),
+		telemetry.as_ref().map(|t| t.handle()),
+	);
+
+	let slot_duration = cumulus_client_consensus_aura::slot_duration(&*client)?;
+	let create_inherent_data_providers = {
+		let client = client.clone();
+		move |parent, ()| {
+			let client = client.clone();
+			async move {
+				let parent_header = client.header(parent)?.ok_or("Parent block not found")?;
+				let parent_slot = parent_header
+					.digest()
+					.logs()
+					.iter()
+					.find_map(|log| {
+						CompatibleDigestItem::<AuthoritySignature>::as_aura_pre_digest(log)
+					})
+					.map_or(0, |slot| *slot);
+				let slot = parent_slot
+					.saturating_add(1)
+					.max(*sp_timestamp::Timestamp::current() / slot_duration.as_millis());
+				let timestamp = sp_timestamp::InherentDataProvider::new(
+					(slot * slot_duration.as_millis()).into(),
+				);
+
+				let parachain_inherent = MockValidationDataInherentDataProvider {
+					current_para_block: *parent_header.number(),
+					relay_offset: 1000,
+					relay_blocks_per_para_block: 2,
+					para_blocks_per_relay_epoch: 10,
+					relay_randomness_config: (),
+					xcm_config: MockXcmConfig::new(&*client, parent, para_id, Default::default()),
+					raw_downward_messages: Vec::new(),
+					raw_horizontal_messages: Vec::new(),
+				};
+
+				Ok::<_, Box<dyn std::error::Error + Send + Sync>>((timestamp, parachain_inherent))
+			}
+		}
+	};
+
+	task_manager.spawn_essential_handle().spawn_blocking(
+		"manual-seal",
+		None,
+		run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env: proposer_factory,
+			client: client.clone(),
+			pool: transaction_pool,
+			commands_stream,
+			select_chain: LongestChain::new(backend),
+			consensus_data_provider: Some(Box::new(AuraConsensusDataProvider::new(client.clone()))),
+			create_inherent_data_providers,
+		}),
+	);
+
+	start_network.start_network();
+
+	Ok((task_manager, client))
+}
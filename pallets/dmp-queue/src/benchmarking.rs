@@ -0,0 +1,59 @@
+//! Benchmarking setup for pallet-parachain-dmp-queue
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as DmpQueue;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::weights::Weight;
+use sp_std::vec;
+
+/// The messages in the page moved by `migrate_page`.
+const PAGE_MESSAGES: usize = 100;
+/// The length of each message moved.
+const MESSAGE_LEN: usize = 1024;
+
+benchmarks! {
+	migrate_page {
+		Pages::<T>::insert(0, vec![(0, vec![0u8; MESSAGE_LEN]); PAGE_MESSAGES]);
+		PageIndex::<T>::put(PageIndexData { begin_used: 0, end_used: 1, overweight_count: 0 });
+		Migration::<T>::put(MigrationState::Pages { next: 0 });
+	}: { DmpQueue::<T>::migrate_step() }
+	verify {
+		assert_eq!(Migration::<T>::get(), MigrationState::Pages { next: 1 });
+		assert!(!Pages::<T>::contains_key(0));
+	}
+
+	migrate_overweight {
+		Overweight::<T>::insert(0, (0, vec![0u8; MESSAGE_LEN]));
+		Migration::<T>::put(MigrationState::Overweight);
+	}: { DmpQueue::<T>::migrate_step() }
+	verify {
+		assert_eq!(Overweight::<T>::count(), 0);
+	}
+
+	complete_migration {
+		Configuration::<T>::put(ConfigData { max_individual: Weight::from_parts(10_000_000, 0) });
+		PageIndex::<T>::put(PageIndexData { begin_used: 1, end_used: 1, overweight_count: 1 });
+		Migration::<T>::put(MigrationState::Overweight);
+	}: { DmpQueue::<T>::migrate_step() }
+	verify {
+		assert_eq!(Migration::<T>::get(), MigrationState::Completed);
+		assert!(!PageIndex::<T>::exists());
+	}
+
+	enqueue_message {
+		let message = vec![0u8; MESSAGE_LEN];
+	}: { DmpQueue::<T>::enqueue(0, &message) }
+
+	defer_page {
+		let messages = vec![(0, vec![0u8; MESSAGE_LEN]); PAGE_MESSAGES];
+		Migration::<T>::put(MigrationState::Completed);
+	}: { DmpQueue::<T>::defer(messages) }
+	verify {
+		assert_eq!(Migration::<T>::get(), MigrationState::Pages { next: 0 });
+		assert!(Pages::<T>::contains_key(0));
+	}
+}
+
+impl_benchmark_test_suite!(DmpQueue, crate::mock::new_test_ext(), crate::mock::Test,);
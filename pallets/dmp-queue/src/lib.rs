@@ -0,0 +1,376 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Takes over from `cumulus_pallet_dmp_queue` once downward messages are queued in
+//! `pallet_message_queue`.
+//!
+//! Mounted under the name of the old pallet, it reads the pages and overweight messages the old
+//! pallet left in its storage and moves them to [`Config::DmpSink`], a few at a time in `on_idle`,
+//! then removes what is left of the old storage. Its storage version replaces that of the old
+//! pallet in `on_runtime_upgrade`. It also lets [`Config::ExecuteOverweightOrigin`]
+//! execute the overweight messages of the new queue, as it could those of the old one.
+//!
+//! It handles downward messages in place of the old pallet too. They are moved to the sink as
+//! long as the weight limit it is given allows, and the rest appended to the old queue as a new
+//! page. While the old queue has pages left to move, every new message is appended behind them,
+//! so that the messages of the relay chain reach the sink in the order they were sent.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::*;
+
+/// The log target of this pallet.
+pub const LOG_TARGET: &str = "runtime::dmp-queue";
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::{WeightInfo, LOG_TARGET};
+	use cumulus_primitives_core::DmpMessageHandler;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{ExecuteOverweightError, HandleMessage, ServiceQueues},
+	};
+	use frame_system::pallet_prelude::*;
+	#[cfg(any(feature = "try-runtime", test))]
+	use sp_runtime::TryRuntimeError;
+	use sp_std::vec::Vec;
+
+	/// The relay chain block number a message was sent at.
+	pub type RelayBlockNumber = u32;
+	/// The index of a page of the old queue.
+	pub type PageCounter = u32;
+	/// The index of an overweight message of the old queue.
+	pub type OverweightIndex = u64;
+
+	/// The configuration of the old pallet.
+	#[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct ConfigData {
+		/// The maximum weight of a message executed as soon as it was received.
+		pub max_individual: Weight,
+	}
+
+	/// The pages of the old queue in use.
+	#[derive(Copy, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+	pub struct PageIndexData {
+		/// The first page in use.
+		pub begin_used: PageCounter,
+		/// The page after the last one in use.
+		pub end_used: PageCounter,
+		/// The number of overweight messages ever recorded.
+		pub overweight_count: OverweightIndex,
+	}
+
+	/// How far the messages of the old queue have been moved.
+	///
+	/// Messages appended to the old queue once its pages were all moved take it back to `Pages`.
+	#[derive(
+		Copy, Clone, Default, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen,
+	)]
+	pub enum MigrationState {
+		/// Nothing has been moved yet.
+		#[default]
+		NotStarted,
+		/// The pages before `next` have been moved.
+		Pages { next: PageCounter },
+		/// All pages have been moved, and the overweight messages are being moved.
+		Overweight,
+		/// Everything has been moved, and the old storage removed.
+		Completed,
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Where the messages of the old queue are moved to.
+		type DmpSink: HandleMessage;
+
+		/// The queue whose overweight messages `ExecuteOverweightOrigin` may execute.
+		type MessageQueue: ServiceQueues<OverweightMessageAddress = Self::OverweightAddress>;
+
+		/// The address of an overweight message in `MessageQueue`.
+		type OverweightAddress: Parameter;
+
+		/// The origin allowed to execute overweight messages.
+		type ExecuteOverweightOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The weight of finding an overweight message in `MessageQueue`, and of updating its page
+		/// and queue once executed, on top of the execution itself. Its proof covers a full page.
+		#[pallet::constant]
+		type OverweightOverhead: Get<Weight>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The current storage version.
+	///
+	/// The old pallet left its own, 2, under the name this pallet is mounted with, so this one
+	/// starts after it.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	/// How far the messages of the old queue have been moved.
+	#[pallet::storage]
+	#[pallet::getter(fn migration_state)]
+	pub type Migration<T: Config> = StorageValue<_, MigrationState, ValueQuery>;
+
+	/// The configuration of the old pallet.
+	#[pallet::storage]
+	pub(super) type Configuration<T: Config> = StorageValue<_, ConfigData>;
+
+	/// The pages of the old queue in use.
+	#[pallet::storage]
+	pub(super) type PageIndex<T: Config> = StorageValue<_, PageIndexData, ValueQuery>;
+
+	/// The messages of the old queue, by page, with the relay chain block they were sent at.
+	#[pallet::storage]
+	pub(super) type Pages<T: Config> =
+		StorageMap<_, Blake2_128Concat, PageCounter, Vec<(RelayBlockNumber, Vec<u8>)>, ValueQuery>;
+
+	/// The overweight messages of the old queue, with the relay chain block they were sent at.
+	#[pallet::storage]
+	pub(super) type Overweight<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, OverweightIndex, (RelayBlockNumber, Vec<u8>)>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The `messages` of page `index` of the old queue were moved.
+		PageMigrated { index: PageCounter, messages: u32 },
+		/// Overweight message `index` of the old queue was moved.
+		OverweightMigrated { index: OverweightIndex },
+		/// A message sent at `sent_at` was dropped, as its `len` is too large for `DmpSink`.
+		MessageDropped { sent_at: RelayBlockNumber, len: u32 },
+		/// All messages of the old queue were moved, and its storage removed.
+		///
+		/// Emitted again whenever the messages appended to it since were all moved.
+		MigrationCompleted,
+		/// The `messages` the weight limit left no room for, or that arrived while the old queue
+		/// had pages left to move, were appended to it as page `index`.
+		MessagesDeferred { index: PageCounter, messages: u32 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// There is no overweight message at the address.
+		OverweightNotFound,
+		/// The weight limit is below the weight of the message.
+		InsufficientWeight,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Replaces the storage version of the old pallet. Its messages are moved in `on_idle`.
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() < STORAGE_VERSION {
+				STORAGE_VERSION.put::<Pallet<T>>();
+				T::DbWeight::get().reads_writes(1, 1)
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == STORAGE_VERSION,
+				"the storage version of the old pallet was not replaced"
+			);
+			Self::do_try_state()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+			Self::do_try_state()
+		}
+
+		fn on_idle(_: BlockNumberFor<T>, limit: Weight) -> Weight {
+			let mut used = T::DbWeight::get().reads(1);
+			loop {
+				let step = match Migration::<T>::get() {
+					MigrationState::Completed => return used,
+					MigrationState::NotStarted | MigrationState::Pages { .. } =>
+						T::WeightInfo::migrate_page(),
+					MigrationState::Overweight =>
+						T::WeightInfo::migrate_overweight().max(T::WeightInfo::complete_migration()),
+				};
+				if !limit.all_gte(used.saturating_add(step)) {
+					return used
+				}
+				used.saturating_accrue(step);
+				Self::migrate_step();
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Execute the overweight message at `address` in `MessageQueue`, with up to
+		/// `weight_limit`.
+		///
+		/// The origin must be `ExecuteOverweightOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::OverweightOverhead::get().saturating_add(*weight_limit))]
+		pub fn execute_overweight(
+			origin: OriginFor<T>,
+			address: T::OverweightAddress,
+			weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			T::ExecuteOverweightOrigin::ensure_origin(origin)?;
+			let used =
+				T::MessageQueue::execute_overweight(weight_limit, address).map_err(|error| {
+					if matches!(error, ExecuteOverweightError::InsufficientWeight) {
+						Error::<T>::InsufficientWeight
+					} else {
+						Error::<T>::OverweightNotFound
+					}
+				})?;
+			Ok(Some(T::OverweightOverhead::get().saturating_add(used)).into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Moves the next page or overweight message of the old queue, or removes the old storage
+		/// once both are empty.
+		pub(crate) fn migrate_step() {
+			let next = match Migration::<T>::get() {
+				MigrationState::NotStarted =>
+					MigrationState::Pages { next: PageIndex::<T>::get().begin_used },
+				MigrationState::Pages { next } if next < PageIndex::<T>::get().end_used => {
+					let messages = Pages::<T>::take(next);
+					let count = messages.len() as u32;
+					for (sent_at, message) in messages {
+						Self::enqueue(sent_at, &message);
+					}
+					Self::deposit_event(Event::PageMigrated { index: next, messages: count });
+					MigrationState::Pages { next: next.saturating_add(1) }
+				},
+				MigrationState::Pages { .. } => MigrationState::Overweight,
+				MigrationState::Overweight => match Overweight::<T>::iter().next() {
+					Some((index, (sent_at, message))) => {
+						Overweight::<T>::remove(index);
+						Self::enqueue(sent_at, &message);
+						Self::deposit_event(Event::OverweightMigrated { index });
+						MigrationState::Overweight
+					},
+					None => {
+						Configuration::<T>::kill();
+						PageIndex::<T>::kill();
+						let _ = Overweight::<T>::clear(u32::MAX, None);
+						Self::deposit_event(Event::MigrationCompleted);
+						MigrationState::Completed
+					},
+				},
+				MigrationState::Completed => return,
+			};
+			Migration::<T>::put(next);
+		}
+
+		/// Appends `messages` to the old queue as a new page, moved after those already in it.
+		pub(crate) fn defer(messages: Vec<(RelayBlockNumber, Vec<u8>)>) {
+			let mut page_index = PageIndex::<T>::get();
+			let index = page_index.end_used;
+			let count = messages.len() as u32;
+			Pages::<T>::insert(index, messages);
+			page_index.end_used = index.saturating_add(1);
+			PageIndex::<T>::put(page_index);
+			if matches!(
+				Migration::<T>::get(),
+				MigrationState::Overweight | MigrationState::Completed
+			) {
+				Migration::<T>::put(MigrationState::Pages { next: index });
+			}
+			Self::deposit_event(Event::MessagesDeferred { index, messages: count });
+		}
+
+		/// Whether the old queue has pages that were not moved yet.
+		fn has_pages_to_move() -> bool {
+			let PageIndexData { begin_used, end_used, .. } = PageIndex::<T>::get();
+			match Migration::<T>::get() {
+				MigrationState::NotStarted => begin_used < end_used,
+				MigrationState::Pages { next } => next < end_used,
+				MigrationState::Overweight | MigrationState::Completed => false,
+			}
+		}
+
+		/// Ensure that nothing is left of the old storage once the migration has completed: no page
+		/// or overweight message, and neither the page index nor the configuration of the old
+		/// pallet.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), TryRuntimeError> {
+			if Migration::<T>::get() != MigrationState::Completed {
+				return Ok(())
+			}
+			ensure!(Pages::<T>::iter_keys().next().is_none(), "pages of the old queue are left");
+			ensure!(
+				Overweight::<T>::count() == 0 && Overweight::<T>::iter_keys().next().is_none(),
+				"overweight messages of the old queue are left"
+			);
+			ensure!(!PageIndex::<T>::exists(), "the page index of the old queue is left");
+			ensure!(!Configuration::<T>::exists(), "the configuration of the old pallet is left");
+			Ok(())
+		}
+
+		pub(crate) fn enqueue(sent_at: RelayBlockNumber, message: &[u8]) {
+			match BoundedSlice::try_from(message) {
+				Ok(message) => T::DmpSink::handle_message(message),
+				Err(_) => {
+					log::warn!(
+						target: LOG_TARGET,
+						"Dropping a message of {} bytes sent at relay chain block {}",
+						message.len(),
+						sent_at,
+					);
+					Self::deposit_event(Event::MessageDropped {
+						sent_at,
+						len: message.len() as u32,
+					});
+				},
+			}
+		}
+	}
+
+	impl<T: Config> DmpMessageHandler for Pallet<T> {
+		fn handle_dmp_messages(
+			iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+			limit: Weight,
+		) -> Weight {
+			let mut used = T::DbWeight::get().reads(2);
+			let mut deferring = Self::has_pages_to_move();
+			let mut deferred = Vec::new();
+			for (sent_at, message) in iter {
+				// The relay chain does not send a message twice, so whatever does not fit in the
+				// limit is deferred, and room is kept for that.
+				let enqueue = T::WeightInfo::enqueue_message();
+				deferring = deferring ||
+					!limit.all_gte(
+						used.saturating_add(enqueue).saturating_add(T::WeightInfo::defer_page()),
+					);
+				if deferring {
+					deferred.push((sent_at, message));
+				} else {
+					used.saturating_accrue(enqueue);
+					Self::enqueue(sent_at, &message);
+				}
+			}
+			if !deferred.is_empty() {
+				used.saturating_accrue(T::WeightInfo::defer_page());
+				Self::defer(deferred);
+			}
+			used
+		}
+	}
+}
@@ -0,0 +1,126 @@
+use frame_support::{
+	parameter_types,
+	traits::{
+		ConstU32, Everything, ExecuteOverweightError, Footprint, HandleMessage, ServiceQueues,
+	},
+	weights::Weight,
+	BoundedSlice,
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+		DmpQueue: crate::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	pub static Enqueued: Vec<Vec<u8>> = vec![];
+	pub const OverweightOverhead: Weight = Weight::from_parts(10, 1_000);
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+/// Records the messages moved to it in `Enqueued`.
+///
+/// Its overweight messages are addressed by their weight in ref time, and there is none at zero.
+pub struct RecordingQueue;
+
+impl HandleMessage for RecordingQueue {
+	type MaxMessageLen = ConstU32<1024>;
+
+	fn handle_message(message: BoundedSlice<u8, Self::MaxMessageLen>) {
+		let mut enqueued = Enqueued::get();
+		enqueued.push(message.to_vec());
+		Enqueued::set(enqueued);
+	}
+
+	fn handle_messages<'a>(
+		messages: impl Iterator<Item = BoundedSlice<'a, u8, Self::MaxMessageLen>>,
+	) {
+		messages.for_each(Self::handle_message);
+	}
+
+	fn sweep_queue() {
+		Enqueued::set(vec![]);
+	}
+
+	fn footprint() -> Footprint {
+		Footprint { count: Enqueued::get().len() as u64, size: 0 }
+	}
+}
+
+impl ServiceQueues for RecordingQueue {
+	type OverweightMessageAddress = u64;
+
+	fn service_queues(_weight_limit: Weight) -> Weight {
+		Weight::zero()
+	}
+
+	fn execute_overweight(
+		weight_limit: Weight,
+		weight: u64,
+	) -> Result<Weight, ExecuteOverweightError> {
+		match weight {
+			0 => Err(ExecuteOverweightError::NotFound),
+			weight if weight_limit.ref_time() < weight =>
+				Err(ExecuteOverweightError::InsufficientWeight),
+			weight => Ok(Weight::from_parts(weight, 0)),
+		}
+	}
+}
+
+impl crate::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type DmpSink = RecordingQueue;
+	type MessageQueue = RecordingQueue;
+	type OverweightAddress = u64;
+	type ExecuteOverweightOrigin = frame_system::EnsureRoot<u64>;
+	type OverweightOverhead = OverweightOverhead;
+	type WeightInfo = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	Enqueued::set(vec![]);
+	let t = system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,226 @@
+use crate::{
+	mock::*, ConfigData, Configuration, Error, Event, Migration, MigrationState, Overweight,
+	PageIndex, PageIndexData, Pages, WeightInfo,
+};
+use cumulus_primitives_core::DmpMessageHandler;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_runtime::DispatchError;
+
+/// Fills the old queue with two pages and an overweight message, as the old pallet left them.
+fn fill_old_queue() {
+	Configuration::<Test>::put(ConfigData { max_individual: Weight::from_parts(10, 0) });
+	PageIndex::<Test>::put(PageIndexData { begin_used: 1, end_used: 3, overweight_count: 2 });
+	Pages::<Test>::insert(1, vec![(5, b"a".to_vec()), (6, b"b".to_vec())]);
+	Pages::<Test>::insert(2, vec![(7, b"c".to_vec())]);
+	Overweight::<Test>::insert(1, (4, b"d".to_vec()));
+}
+
+fn events() -> Vec<Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::DmpQueue(event) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn moves_pages_then_overweight_messages_then_removes_old_storage() {
+	new_test_ext().execute_with(|| {
+		fill_old_queue();
+
+		DmpQueue::on_idle(1, Weight::MAX);
+
+		assert_eq!(
+			Enqueued::get(),
+			vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+		);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Completed);
+		assert_eq!(Pages::<Test>::iter().count(), 0);
+		assert_eq!(Overweight::<Test>::count(), 0);
+		assert!(!PageIndex::<Test>::exists());
+		assert!(!Configuration::<Test>::exists());
+		assert_eq!(
+			events(),
+			vec![
+				Event::PageMigrated { index: 1, messages: 2 },
+				Event::PageMigrated { index: 2, messages: 1 },
+				Event::OverweightMigrated { index: 1 },
+				Event::MigrationCompleted,
+			]
+		);
+	});
+}
+
+#[test]
+fn moves_no_more_than_the_idle_weight_allows() {
+	new_test_ext().execute_with(|| {
+		fill_old_queue();
+		let read = <Test as frame_system::Config>::DbWeight::get().reads(1);
+		let page = <() as WeightInfo>::migrate_page();
+
+		// Finding the first page, then moving it.
+		assert_eq!(
+			DmpQueue::on_idle(1, read + page.saturating_mul(2)),
+			read + page.saturating_mul(2)
+		);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Pages { next: 2 });
+		assert_eq!(Enqueued::get(), vec![b"a".to_vec(), b"b".to_vec()]);
+
+		assert_eq!(
+			DmpQueue::on_idle(2, read + page.saturating_sub(Weight::from_parts(1, 0))),
+			read
+		);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Pages { next: 2 });
+	});
+}
+
+#[test]
+fn drops_messages_too_large_for_the_sink() {
+	new_test_ext().execute_with(|| {
+		PageIndex::<Test>::put(PageIndexData { begin_used: 0, end_used: 1, overweight_count: 0 });
+		Pages::<Test>::insert(0, vec![(5, vec![0; 1025]), (6, vec![0; 1024])]);
+
+		DmpQueue::on_idle(1, Weight::MAX);
+
+		assert_eq!(Enqueued::get(), vec![vec![0; 1024]]);
+		assert!(events().contains(&Event::MessageDropped { sent_at: 5, len: 1025 }));
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Completed);
+	});
+}
+
+#[test]
+fn does_nothing_once_completed() {
+	new_test_ext().execute_with(|| {
+		Migration::<Test>::put(MigrationState::Completed);
+		Pages::<Test>::insert(0, vec![(5, b"a".to_vec())]);
+
+		let read = <Test as frame_system::Config>::DbWeight::get().reads(1);
+		assert_eq!(DmpQueue::on_idle(1, Weight::MAX), read);
+		assert!(Enqueued::get().is_empty());
+		assert!(Pages::<Test>::contains_key(0));
+	});
+}
+
+#[test]
+fn upgrade_replaces_the_storage_version_of_the_old_pallet() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(2).put::<DmpQueue>();
+
+		<DmpQueue as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(DmpQueue::on_chain_storage_version(), StorageVersion::new(3));
+		assert_eq!(DmpQueue::on_chain_storage_version(), DmpQueue::current_storage_version());
+	});
+}
+
+#[test]
+fn try_state_checks_the_old_storage_is_gone_once_completed() {
+	new_test_ext().execute_with(|| {
+		fill_old_queue();
+		assert_ok!(DmpQueue::do_try_state());
+
+		DmpQueue::on_idle(1, Weight::MAX);
+		assert_ok!(DmpQueue::do_try_state());
+
+		Overweight::<Test>::insert(2, (4, b"d".to_vec()));
+		assert!(DmpQueue::do_try_state().is_err());
+		Overweight::<Test>::remove(2);
+		assert_ok!(DmpQueue::do_try_state());
+
+		Configuration::<Test>::put(ConfigData { max_individual: Weight::zero() });
+		assert!(DmpQueue::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn defers_downward_messages_beyond_the_weight_limit() {
+	new_test_ext().execute_with(|| {
+		let enqueue = <() as WeightInfo>::enqueue_message();
+		let defer = <() as WeightInfo>::defer_page();
+		let messages = vec![(1, b"a".to_vec()), (1, b"b".to_vec()), (2, b"c".to_vec())];
+
+		let limit = enqueue.saturating_mul(2) + defer;
+		assert_eq!(DmpQueue::handle_dmp_messages(messages.into_iter(), limit), limit);
+		assert_eq!(Enqueued::get(), vec![b"a".to_vec(), b"b".to_vec()]);
+		assert_eq!(events(), vec![Event::MessagesDeferred { index: 0, messages: 1 }]);
+
+		// Later messages wait behind the deferred one.
+		DmpQueue::handle_dmp_messages(vec![(3, b"d".to_vec())].into_iter(), Weight::MAX);
+		assert_eq!(Enqueued::get(), vec![b"a".to_vec(), b"b".to_vec()]);
+
+		DmpQueue::on_idle(1, Weight::MAX);
+		assert_eq!(
+			Enqueued::get(),
+			vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"d".to_vec()]
+		);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Completed);
+	});
+}
+
+#[test]
+fn new_downward_messages_wait_behind_the_old_queue() {
+	new_test_ext().execute_with(|| {
+		fill_old_queue();
+
+		DmpQueue::handle_dmp_messages(vec![(8, b"e".to_vec())].into_iter(), Weight::MAX);
+		assert!(Enqueued::get().is_empty());
+
+		DmpQueue::on_idle(1, Weight::MAX);
+		assert_eq!(
+			Enqueued::get(),
+			vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec(), b"e".to_vec(), b"d".to_vec()]
+		);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Completed);
+	});
+}
+
+#[test]
+fn deferring_after_completion_moves_the_new_page() {
+	new_test_ext().execute_with(|| {
+		Migration::<Test>::put(MigrationState::Completed);
+
+		DmpQueue::handle_dmp_messages(vec![(8, b"e".to_vec())].into_iter(), Weight::zero());
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Pages { next: 0 });
+
+		DmpQueue::on_idle(1, Weight::MAX);
+		assert_eq!(Enqueued::get(), vec![b"e".to_vec()]);
+		assert_eq!(DmpQueue::migration_state(), MigrationState::Completed);
+		assert!(!PageIndex::<Test>::exists());
+	});
+}
+
+#[test]
+fn admin_executes_overweight_messages() {
+	new_test_ext().execute_with(|| {
+		let post_info =
+			DmpQueue::execute_overweight(RuntimeOrigin::root(), 100, Weight::from_parts(100, 0))
+				.unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(Weight::from_parts(100, 0).saturating_add(OverweightOverhead::get()))
+		);
+		assert_noop!(
+			DmpQueue::execute_overweight(RuntimeOrigin::root(), 100, Weight::from_parts(99, 0)),
+			Error::<Test>::InsufficientWeight
+		);
+		assert_noop!(
+			DmpQueue::execute_overweight(RuntimeOrigin::root(), 0, Weight::MAX),
+			Error::<Test>::OverweightNotFound
+		);
+	});
+}
+
+#[test]
+fn execute_overweight_requires_admin_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			DmpQueue::execute_overweight(RuntimeOrigin::signed(1), 100, Weight::MAX),
+			DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,271 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Queues the XCM this chain receives from siblings in `pallet_message_queue`, and shares out the
+//! execution of the queued messages between the relay chain and siblings.
+//!
+//! The pallet stands in for the XCM executor of `cumulus_pallet_xcmp_queue`, and queues the
+//! messages of siblings in [`Config::MessageQueue`] rather than executing them. While the queue of
+//! a sibling holds [`Config::SiblingQueueCapacity`] messages, as reported to the pallet through
+//! `OnQueueChanged`, the messages of that sibling are reported to `XcmpQueue` as over the weight
+//! limit, so that it keeps them and offers them again later.
+//!
+//! The pallet also executes the queued messages with [`Config::MessageProcessor`], within
+//! [`Config::ParentServiceWeight`] for those of the relay chain and
+//! [`Config::SiblingServiceWeight`] for those of siblings in each block, so that neither can crowd
+//! out the other.
+//!
+//! Taking a message from `XcmpQueue` weighs [`Config::EnqueueWeight`], whose proof covers a full
+//! page of `MessageQueue`. `XcmpQueue` sets aside as overweight the messages it cannot take in a
+//! block when they need more than its maximum weight for a single message, so that maximum has to
+//! be raised above the weight of queueing a message, which [`Config::OnGenesis`] can do for a new
+//! chain.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use cumulus_primitives_core::ParaId;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{EnqueueMessage, OnGenesis, OnQueueChanged, ProcessMessage, ProcessMessageError},
+		weights::WeightMeter,
+	};
+	use frame_system::pallet_prelude::*;
+	use xcm::{
+		latest::{
+			Error as XcmError, ExecuteXcm, Junction::Parachain, Junctions::X1, MultiAssets,
+			MultiLocation, Outcome, PreparedMessage, Result as XcmResult, Xcm, XcmHash,
+		},
+		VersionedXcm,
+	};
+
+	/// The origins of the messages queued in `MessageQueue`.
+	#[derive(Encode, Decode, MaxEncodedLen, Clone, Copy, Eq, PartialEq, TypeInfo, RuntimeDebug)]
+	pub enum AggregateMessageOrigin {
+		/// Downward messages from the relay chain.
+		Parent,
+		/// Horizontal messages from a sibling parachain.
+		Sibling(ParaId),
+	}
+
+	impl From<AggregateMessageOrigin> for MultiLocation {
+		fn from(origin: AggregateMessageOrigin) -> Self {
+			match origin {
+				AggregateMessageOrigin::Parent => MultiLocation::parent(),
+				AggregateMessageOrigin::Sibling(para) =>
+					MultiLocation::new(1, X1(Parachain(para.into()))),
+			}
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl From<u32> for AggregateMessageOrigin {
+		fn from(para: u32) -> Self {
+			AggregateMessageOrigin::Sibling(para.into())
+		}
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Executes the messages queued in `MessageQueue`.
+		type MessageProcessor: ProcessMessage<Origin = AggregateMessageOrigin>;
+
+		/// The queue the messages of siblings are queued in.
+		type MessageQueue: EnqueueMessage<AggregateMessageOrigin>;
+
+		/// Charges the fees of the messages `XcmpQueue` sends.
+		type XcmExecutor: ExecuteXcm<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// The weight the messages of the relay chain may use in each block.
+		#[pallet::constant]
+		type ParentServiceWeight: Get<Weight>;
+
+		/// The weight the messages of siblings may use in each block.
+		#[pallet::constant]
+		type SiblingServiceWeight: Get<Weight>;
+
+		/// How many messages of a sibling `MessageQueue` holds before `XcmpQueue` has to keep the
+		/// next ones for it.
+		#[pallet::constant]
+		type SiblingQueueCapacity: Get<u64>;
+
+		/// The weight of queueing a message in `MessageQueue`, its proof covering a full page.
+		#[pallet::constant]
+		type EnqueueWeight: Get<Weight>;
+
+		/// Sets up the chain at genesis, such as raising the weight `XcmpQueue` lets a single
+		/// message use.
+		type OnGenesis: OnGenesis;
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		#[serde(skip)]
+		pub _config: sp_std::marker::PhantomData<T>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			T::OnGenesis::on_genesis();
+		}
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The block, and the weight `MessageQueue` spent in it on the messages of the relay chain and
+	/// on those of siblings.
+	#[pallet::storage]
+	pub type ServiceWeightUsed<T: Config> =
+		StorageValue<_, (BlockNumberFor<T>, Weight, Weight), ValueQuery>;
+
+	/// The siblings whose queue in `MessageQueue` is at `SiblingQueueCapacity`.
+	#[pallet::storage]
+	pub type CongestedSiblings<T: Config> = StorageMap<_, Twox64Concat, ParaId, ()>;
+
+	/// What FRAME's benchmarks add to the proof of a storage value, or of an item of a map without
+	/// a bound on its length, for the trie nodes on the way to it.
+	const VALUE_TRIE_PROOF: usize = 495;
+	const MAP_TRIE_PROOF: usize = 2_475;
+
+	impl<T: Config> Pallet<T> {
+		/// The weight of reading and writing `ServiceWeightUsed`.
+		fn bookkeeping_weight() -> Weight {
+			let proof = ServiceWeightUsed::<T>::hashed_key().len() +
+				<(BlockNumberFor<T>, Weight, Weight)>::max_encoded_len() +
+				VALUE_TRIE_PROOF;
+			T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(Weight::from_parts(0, proof as u64))
+		}
+
+		/// The weight of checking the congestion of the queue of a sibling, queueing its message
+		/// in `MessageQueue`, and updating the congestion as the queue reports its new length.
+		fn enqueue_weight() -> Weight {
+			let proof =
+				CongestedSiblings::<T>::hashed_key_for(ParaId::from(0)).len() + MAP_TRIE_PROOF;
+			T::DbWeight::get()
+				.reads_writes(1, 1)
+				.saturating_add(Weight::from_parts(0, proof as u64))
+				.saturating_add(T::EnqueueWeight::get())
+		}
+	}
+
+	/// Executes the messages with `MessageProcessor`, within the share of the block of their
+	/// origin.
+	///
+	/// Once the relay chain or siblings have used their share, their queues yield to the others
+	/// until the next block. The share is checked before a message is executed, so the last one
+	/// may overrun it by its own weight.
+	impl<T: Config> ProcessMessage for Pallet<T> {
+		type Origin = AggregateMessageOrigin;
+
+		fn process_message(
+			message: &[u8],
+			origin: Self::Origin,
+			meter: &mut WeightMeter,
+			id: &mut [u8; 32],
+		) -> Result<bool, ProcessMessageError> {
+			let bookkeeping = Self::bookkeeping_weight();
+			if !meter.check_accrue(bookkeeping) {
+				return Err(ProcessMessageError::Overweight(bookkeeping))
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			let (block, mut parent, mut sibling) = ServiceWeightUsed::<T>::get();
+			if block != now {
+				(parent, sibling) = (Weight::zero(), Weight::zero());
+			}
+			let (used, share) = match origin {
+				AggregateMessageOrigin::Parent => (&mut parent, T::ParentServiceWeight::get()),
+				AggregateMessageOrigin::Sibling(_) =>
+					(&mut sibling, T::SiblingServiceWeight::get()),
+			};
+			if used.any_gte(share) {
+				return Err(ProcessMessageError::Yield)
+			}
+
+			let consumed = meter.consumed;
+			let result = T::MessageProcessor::process_message(message, origin, meter, id);
+			used.saturating_accrue(meter.consumed.saturating_sub(consumed));
+			ServiceWeightUsed::<T>::put((now, parent, sibling));
+			result
+		}
+	}
+
+	/// Keeps `CongestedSiblings` up to date as the queues of `MessageQueue` grow and drain.
+	impl<T: Config> OnQueueChanged<AggregateMessageOrigin> for Pallet<T> {
+		fn on_queue_changed(origin: AggregateMessageOrigin, count: u64, _size: u64) {
+			if let AggregateMessageOrigin::Sibling(para) = origin {
+				if count >= T::SiblingQueueCapacity::get() {
+					CongestedSiblings::<T>::insert(para, ());
+				} else {
+					CongestedSiblings::<T>::remove(para);
+				}
+			}
+		}
+	}
+
+	/// An XCM `XcmpQueue` received from a sibling, to be queued in `MessageQueue`.
+	pub struct SiblingXcm<T: Config>(Xcm<<T as frame_system::Config>::RuntimeCall>);
+
+	impl<T: Config> PreparedMessage for SiblingXcm<T> {
+		fn weight_of(&self) -> Weight {
+			Pallet::<T>::enqueue_weight()
+		}
+	}
+
+	/// Queues the messages `XcmpQueue` received in `MessageQueue`, where they are executed with the
+	/// sending sibling as origin.
+	///
+	/// `XcmpQueue` keeps decoding the pages, handling the channel signals and asking siblings to
+	/// suspend their channel when it holds too many of their pages. A message that finds the queue
+	/// of its sibling congested is reported to it as over the weight limit, so that it keeps the
+	/// message and those after it, and offers them again once `MessageQueue` has executed some.
+	impl<T: Config> ExecuteXcm<<T as frame_system::Config>::RuntimeCall> for Pallet<T> {
+		type Prepared = SiblingXcm<T>;
+
+		fn prepare(
+			message: Xcm<<T as frame_system::Config>::RuntimeCall>,
+		) -> Result<Self::Prepared, Xcm<<T as frame_system::Config>::RuntimeCall>> {
+			Ok(SiblingXcm(message))
+		}
+
+		fn execute(
+			origin: impl Into<MultiLocation>,
+			pre: Self::Prepared,
+			_id: &mut XcmHash,
+			_weight_credit: Weight,
+		) -> Outcome {
+			let weight = pre.weight_of();
+			let para = match origin.into() {
+				MultiLocation { parents: 1, interior: X1(Parachain(para)) } => ParaId::from(para),
+				_ => return Outcome::Error(XcmError::InvalidLocation),
+			};
+			if CongestedSiblings::<T>::contains_key(para) {
+				return Outcome::Error(XcmError::WeightLimitReached(weight))
+			}
+			let message = VersionedXcm::from(pre.0).encode();
+			match BoundedSlice::try_from(&message[..]) {
+				Ok(message) => {
+					T::MessageQueue::enqueue_message(
+						message,
+						AggregateMessageOrigin::Sibling(para),
+					);
+					Outcome::Complete(weight)
+				},
+				Err(_) => Outcome::Error(XcmError::ExceedsMaxMessageSize),
+			}
+		}
+
+		fn charge_fees(location: impl Into<MultiLocation>, fees: MultiAssets) -> XcmResult {
+			T::XcmExecutor::charge_fees(location, fees)
+		}
+	}
+}
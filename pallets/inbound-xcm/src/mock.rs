@@ -0,0 +1,140 @@
+use crate::AggregateMessageOrigin;
+use frame_support::{
+	parameter_types,
+	traits::{
+		ConstU32, ConstU64, EnqueueMessage, Everything, Footprint, ProcessMessage,
+		ProcessMessageError,
+	},
+	weights::{Weight, WeightMeter},
+	BoundedSlice,
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	BuildStorage,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+		InboundXcm: crate::{Pallet, Storage, Config<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	/// The weight `RecordingProcessor` uses for every message.
+	pub const MessageWeight: Weight = Weight::from_parts(10, 10);
+	pub const ParentServiceWeight: Weight = Weight::from_parts(25, 25);
+	pub const SiblingServiceWeight: Weight = Weight::from_parts(25, 25);
+	pub const EnqueueWeight: Weight = Weight::from_parts(5, 1_000);
+	pub static Enqueued: Vec<(AggregateMessageOrigin, Vec<u8>)> = vec![];
+	pub static Processed: Vec<(AggregateMessageOrigin, Vec<u8>)> = vec![];
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Nonce = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+/// Records the messages queued in it in `Enqueued`.
+pub struct RecordingQueue;
+
+impl EnqueueMessage<AggregateMessageOrigin> for RecordingQueue {
+	type MaxMessageLen = ConstU32<64>;
+
+	fn enqueue_message(
+		message: BoundedSlice<u8, Self::MaxMessageLen>,
+		origin: AggregateMessageOrigin,
+	) {
+		let mut enqueued = Enqueued::get();
+		enqueued.push((origin, message.to_vec()));
+		Enqueued::set(enqueued);
+	}
+
+	fn enqueue_messages<'a>(
+		messages: impl Iterator<Item = BoundedSlice<'a, u8, Self::MaxMessageLen>>,
+		origin: AggregateMessageOrigin,
+	) {
+		messages.for_each(|message| Self::enqueue_message(message, origin));
+	}
+
+	fn sweep_queue(origin: AggregateMessageOrigin) {
+		Enqueued::set(Enqueued::get().into_iter().filter(|(o, _)| *o != origin).collect());
+	}
+
+	fn footprint(origin: AggregateMessageOrigin) -> Footprint {
+		let count = Enqueued::get().iter().filter(|(o, _)| *o == origin).count();
+		Footprint { count: count as u64, size: 0 }
+	}
+}
+
+/// Records the messages it executes in `Processed`, each using `MessageWeight`.
+pub struct RecordingProcessor;
+
+impl ProcessMessage for RecordingProcessor {
+	type Origin = AggregateMessageOrigin;
+
+	fn process_message(
+		message: &[u8],
+		origin: Self::Origin,
+		meter: &mut WeightMeter,
+		_id: &mut [u8; 32],
+	) -> Result<bool, ProcessMessageError> {
+		if !meter.check_accrue(MessageWeight::get()) {
+			return Err(ProcessMessageError::Overweight(MessageWeight::get()))
+		}
+		let mut processed = Processed::get();
+		processed.push((origin, message.to_vec()));
+		Processed::set(processed);
+		Ok(true)
+	}
+}
+
+impl crate::Config for Test {
+	type MessageProcessor = RecordingProcessor;
+	type MessageQueue = RecordingQueue;
+	type XcmExecutor = ();
+	type ParentServiceWeight = ParentServiceWeight;
+	type SiblingServiceWeight = SiblingServiceWeight;
+	type SiblingQueueCapacity = ConstU64<2>;
+	type EnqueueWeight = EnqueueWeight;
+	type OnGenesis = ();
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	Enqueued::set(vec![]);
+	Processed::set(vec![]);
+	let t = system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
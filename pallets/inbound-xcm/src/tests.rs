@@ -0,0 +1,125 @@
+use crate::{mock::*, AggregateMessageOrigin, CongestedSiblings};
+use codec::Encode;
+use cumulus_primitives_core::ParaId;
+use frame_support::{
+	traits::{OnQueueChanged, ProcessMessage, ProcessMessageError},
+	weights::{Weight, WeightMeter},
+};
+use xcm::{latest::prelude::*, VersionedXcm};
+
+fn sibling(para: u32) -> MultiLocation {
+	MultiLocation::new(1, X1(Parachain(para)))
+}
+
+/// Has the pallet take `message` from `origin`, as `XcmpQueue` would.
+fn execute(origin: MultiLocation, message: Xcm<RuntimeCall>) -> Outcome {
+	let Ok(prepared) = <InboundXcm as ExecuteXcm<RuntimeCall>>::prepare(message) else {
+		panic!("preparing a message never fails")
+	};
+	<InboundXcm as ExecuteXcm<RuntimeCall>>::execute(origin, prepared, &mut [0; 32], Weight::zero())
+}
+
+fn process(
+	origin: AggregateMessageOrigin,
+	meter: &mut WeightMeter,
+) -> Result<bool, ProcessMessageError> {
+	InboundXcm::process_message(b"message", origin, meter, &mut [0; 32])
+}
+
+#[test]
+fn queues_the_messages_of_siblings() {
+	new_test_ext().execute_with(|| {
+		let message = Xcm::<RuntimeCall>(vec![ClearOrigin]);
+		assert!(matches!(execute(sibling(2000), message.clone()), Outcome::Complete(_)));
+		assert_eq!(
+			Enqueued::get(),
+			vec![(
+				AggregateMessageOrigin::Sibling(2000.into()),
+				VersionedXcm::from(message).encode()
+			)]
+		);
+	});
+}
+
+#[test]
+fn rejects_messages_not_from_a_sibling() {
+	new_test_ext().execute_with(|| {
+		let message = Xcm::<RuntimeCall>(vec![ClearOrigin]);
+		for origin in
+			[MultiLocation::parent(), MultiLocation::new(1, X2(Parachain(2000), PalletInstance(1)))]
+		{
+			assert_eq!(execute(origin, message.clone()), Outcome::Error(XcmError::InvalidLocation));
+		}
+		assert!(Enqueued::get().is_empty());
+	});
+}
+
+#[test]
+fn rejects_messages_too_large_for_the_queue() {
+	new_test_ext().execute_with(|| {
+		let message = Xcm::<RuntimeCall>(vec![Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::zero(),
+			call: vec![0u8; 64].into(),
+		}]);
+		assert_eq!(
+			execute(sibling(2000), message),
+			Outcome::Error(XcmError::ExceedsMaxMessageSize)
+		);
+		assert!(Enqueued::get().is_empty());
+	});
+}
+
+#[test]
+fn holds_back_the_messages_of_congested_siblings() {
+	new_test_ext().execute_with(|| {
+		let message = Xcm::<RuntimeCall>(vec![ClearOrigin]);
+		InboundXcm::on_queue_changed(AggregateMessageOrigin::Sibling(2000.into()), 2, 0);
+		assert!(CongestedSiblings::<Test>::contains_key(ParaId::from(2000)));
+
+		assert!(matches!(
+			execute(sibling(2000), message.clone()),
+			Outcome::Error(XcmError::WeightLimitReached(_))
+		));
+		assert!(matches!(execute(sibling(3000), message.clone()), Outcome::Complete(_)));
+		assert_eq!(Enqueued::get().len(), 1);
+
+		InboundXcm::on_queue_changed(AggregateMessageOrigin::Sibling(2000.into()), 1, 0);
+		assert!(!CongestedSiblings::<Test>::contains_key(ParaId::from(2000)));
+		assert!(matches!(execute(sibling(2000), message), Outcome::Complete(_)));
+		assert_eq!(Enqueued::get().len(), 2);
+	});
+}
+
+#[test]
+fn relay_chain_and_siblings_keep_to_their_share() {
+	new_test_ext().execute_with(|| {
+		let sibling = AggregateMessageOrigin::Sibling(2000.into());
+		let mut meter = WeightMeter::max_limit();
+
+		// A share of 25 lets three messages of 10 start, the last one overrunning it.
+		for _ in 0..3 {
+			assert_eq!(process(sibling, &mut meter), Ok(true));
+		}
+		assert_eq!(process(sibling, &mut meter), Err(ProcessMessageError::Yield));
+		assert_eq!(process(AggregateMessageOrigin::Parent, &mut meter), Ok(true));
+		assert_eq!(Processed::get().len(), 4);
+
+		// The shares are renewed in every block.
+		System::set_block_number(2);
+		assert_eq!(process(sibling, &mut meter), Ok(true));
+		assert_eq!(Processed::get().len(), 5);
+	});
+}
+
+#[test]
+fn weighs_queueing_with_its_proof() {
+	new_test_ext().execute_with(|| {
+		let Outcome::Complete(weight) = execute(sibling(2000), Xcm(vec![ClearOrigin])) else {
+			panic!("the message is queued")
+		};
+		assert!(weight.all_gte(EnqueueWeight::get()));
+		// Checking the congestion of the sibling adds to the proof of queueing the message.
+		assert!(weight.proof_size() > EnqueueWeight::get().proof_size());
+	});
+}
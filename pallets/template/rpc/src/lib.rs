@@ -0,0 +1,112 @@
+//! RPC interface for the template pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_parachain_template_runtime_api::TemplateApi as TemplateRuntimeApi;
+
+/// The value stored by an account, as returned over RPC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountValueInfo<Balance> {
+	/// The value stored by the account.
+	pub value: u32,
+	/// The amount held from the account for this entry.
+	pub deposit: Balance,
+}
+
+#[rpc(client, server)]
+pub trait TemplateApi<BlockHash, AccountId, Balance> {
+	/// Get the value most recently stored through the pallet.
+	#[method(name = "template_getSomething")]
+	fn something(&self, at: Option<BlockHash>) -> RpcResult<Option<u32>>;
+
+	/// Get the value stored by `who` and the deposit held for it.
+	#[method(name = "template_getAccountValue")]
+	fn account_value(
+		&self,
+		who: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<AccountValueInfo<Balance>>>;
+}
+
+/// Provides RPC methods to query the state of the template pallet.
+pub struct Template<C, P> {
+	/// Shared reference to the client.
+	client: Arc<C>,
+	_marker: PhantomData<P>,
+}
+
+impl<C, P> Template<C, P> {
+	/// Creates a new instance of the Template RPC helper.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId, Balance> TemplateApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+	for Template<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: TemplateRuntimeApi<Block, AccountId, Balance>,
+	AccountId: Codec + DeserializeOwned + Send + Sync + 'static,
+	Balance: Codec + Serialize + Send + Sync + 'static,
+{
+	fn something(&self, at: Option<Block::Hash>) -> RpcResult<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		api.something(at_hash).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to query the stored value.",
+				Some(e.to_string()),
+			))
+			.into()
+		})
+	}
+
+	fn account_value(
+		&self,
+		who: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<AccountValueInfo<Balance>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let entry = api.account_value(at_hash, who).map_err(|e| {
+			CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to query the account value.",
+				Some(e.to_string()),
+			))
+		})?;
+
+		Ok(entry.map(|entry| AccountValueInfo { value: entry.value, deposit: entry.deposit }))
+	}
+}
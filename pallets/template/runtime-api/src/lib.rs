@@ -0,0 +1,22 @@
+//! Runtime API definition for the template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_parachain_template::AccountValue;
+
+sp_api::decl_runtime_apis! {
+	/// Typed access to the state of the template pallet, independent of its storage layout.
+	pub trait TemplateApi<AccountId, Balance>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+	{
+		/// The value most recently stored through the pallet, if any.
+		fn something() -> Option<u32>;
+
+		/// The value stored by `who` and the deposit held for it, if any.
+		fn account_value(who: AccountId) -> Option<AccountValue<Balance>>;
+	}
+}
@@ -4,16 +4,97 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use codec::Decode;
+use frame_benchmarking::{
+	benchmarks, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
+};
+use frame_support::traits::fungible::{Inspect, Mutate};
 use frame_system::RawOrigin;
+use sp_runtime::traits::{TrailingZeroInput, Zero};
+
+/// Give `who` enough funds to cover the deposit of a stored value.
+fn fund<T: Config>(who: &T::AccountId) {
+	let amount = T::Currency::minimum_balance() + T::SomethingDeposit::get();
+	T::Currency::set_balance(who, amount);
+}
+
+/// Fill `History` with `h` changes. Once it holds `MaxHistory`, recording a change has to drop
+/// the oldest one.
+fn fill_history<T: Config>(h: u32) {
+	let who: T::AccountId = whitelisted_caller();
+	for new in 0..h {
+		let change = ChangeRecord { who: who.clone(), old: None, new, block_number: Zero::zero() };
+		History::<T>::mutate(|history| {
+			let _ = history.force_insert_keep_right(history.len(), change);
+		});
+	}
+}
 
 benchmarks! {
+	// Worst case: the caller has no entry yet, so the deposit has to be held.
 	do_something {
-		let s in 0 .. 100;
+		let h in 0 .. T::MaxHistory::get();
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		fill_history::<T>(h);
+	}: _(RawOrigin::Signed(caller.clone()), 42)
+	verify {
+		assert_eq!(Something::<T>::get().map(|record| record.value), Some(42));
+		assert_eq!(AccountValues::<T>::get(&caller).map(|entry| entry.value), Some(42));
+	}
+
+	cause_error {
+		let h in 0 .. T::MaxHistory::get();
+		let caller: T::AccountId = whitelisted_caller();
+		Something::<T>::put(SomethingRecord { value: u32::MAX - 1, updated_at: Zero::zero() });
+		fill_history::<T>(h);
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Something::<T>::get().map(|record| record.value), Some(u32::MAX));
+	}
+
+	cause_error_overflow {
+		let caller: T::AccountId = whitelisted_caller();
+		Something::<T>::put(SomethingRecord { value: u32::MAX, updated_at: Zero::zero() });
+	}: {
+		assert!(Template::<T>::cause_error(RawOrigin::Signed(caller).into()).is_err());
+	}
+	verify {
+		assert_eq!(Something::<T>::get().map(|record| record.value), Some(u32::MAX));
+	}
+
+	clear_something {
 		let caller: T::AccountId = whitelisted_caller();
-	}: _(RawOrigin::Signed(caller), s)
+		fund::<T>(&caller);
+		Template::<T>::do_something(RawOrigin::Signed(caller.clone()).into(), 42)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(AccountValues::<T>::get(&caller), None);
+	}
+
+	// The signature is checked in `validate_unsigned`, not in the dispatchable itself.
+	submit_value_unsigned {
+		let h in 0 .. T::MaxHistory::get();
+		let public = T::Public::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite input is enough to decode a public key; qed");
+		let signature = T::Signature::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite input is enough to decode a signature; qed");
+		let payload = ValuePayload { block_number: Zero::zero(), value: 42, public };
+		fill_history::<T>(h);
+	}: _(RawOrigin::None, payload, signature)
+	verify {
+		assert_eq!(Something::<T>::get().map(|record| record.value), Some(42));
+	}
+
+	// Worst case: `Something` is set, and checked against the new `max_value`.
+	set_parameters {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		Something::<T>::put(SomethingRecord { value: 100, updated_at: Zero::zero() });
+		let parameters = TemplateParameters { max_value: 100, overflow: OverflowBehaviour::Wrap };
+	}: _<T::RuntimeOrigin>(origin, parameters)
 	verify {
-		assert_eq!(Something::<T>::get(), Some(s));
+		assert_eq!(Parameters::<T>::get(), parameters);
 	}
 }
 
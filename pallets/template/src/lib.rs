@@ -13,38 +13,312 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::*;
+
+use sp_core::crypto::KeyTypeId;
+
+/// The log target of this pallet.
+pub const LOG_TARGET: &str = "runtime::template";
+
+/// The key type of the keys used by the offchain worker to sign its transactions.
+///
+/// A key of this type has to be inserted into the keystore of the node, e.g. through the
+/// `author_insertKey` RPC, for the offchain worker to submit anything.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// The local offchain storage key under which the offchain worker records its progress.
+pub const OFFCHAIN_PROGRESS_KEY: &[u8] = b"parachain-template::offchain-progress";
+
+/// Crypto used by the offchain worker to sign transactions.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// The identifier of the offchain worker's signing key.
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature>
+		for TemplateAuthId
+	{
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
-	use frame_system::pallet_prelude::*;
+	use super::WeightInfo;
+	use frame_support::{
+		dispatch::{DispatchResultWithPostInfo, WithPostDispatchInfo},
+		pallet_prelude::*,
+		traits::{
+			fungible::{self, MutateHold},
+			tokens::Precision,
+			Contains,
+		},
+	};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+			SignedPayload, Signer, SigningTypes,
+		},
+		pallet_prelude::*,
+	};
+	#[cfg(any(feature = "try-runtime", test))]
+	use sp_runtime::TryRuntimeError;
+	use sp_runtime::{
+		offchain::storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
+		traits::{IdentifyAccount, SaturatedConversion, Saturating, Zero},
+	};
+	use sp_std::vec::Vec;
+
+	/// The balance type of the currency used to hold deposits.
+	pub type BalanceOf<T> = <<T as Config>::Currency as fungible::Inspect<
+		<T as frame_system::Config>::AccountId,
+	>>::Balance;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The overarching hold reason.
+		type RuntimeHoldReason: From<HoldReason>;
+
+		/// The currency used to hold the deposit of every stored value.
+		///
+		/// `do_try_state` looks for deposits held without an entry over the accounts of
+		/// `frame_system`, so the accounts of this currency must be kept there, as
+		/// `pallet_balances` does with `AccountStore = System`.
+		type Currency: fungible::Mutate<Self::AccountId>
+			+ MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+		/// The amount held from an account for as long as it has a value stored.
+		#[pallet::constant]
+		type SomethingDeposit: Get<BalanceOf<Self>>;
+
+		/// The identifier type for the offchain worker's signing key.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The accounts whose keys may sign values submitted through `submit_value_unsigned`.
+		type AuthorizedReporters: Contains<Self::AccountId>;
+
+		/// The minimum number of blocks between two submissions of the offchain worker.
+		///
+		/// This is also the minimum number of blocks between two accepted unsigned submissions.
+		#[pallet::constant]
+		type SubmissionInterval: Get<BlockNumberFor<Self>>;
+
+		/// Whether the offchain worker submits values through `submit_value_unsigned` rather
+		/// than through signed `do_something` transactions.
+		#[pallet::constant]
+		type SubmitUnsigned: Get<bool>;
+
+		/// The priority of unsigned transactions submitting a value.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// The origin allowed to change the pallet's `Parameters`.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The number of most recent changes of `Something` kept in `History`.
+		#[pallet::constant]
+		type MaxHistory: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// A reason for the pallet placing a hold on funds.
+	#[pallet::composite_enum]
+	pub enum HoldReason {
+		/// Funds are held as the deposit for a value stored by the account.
+		SomethingDeposit,
+	}
+
+	/// What `cause_error` does when incrementing `Something` would exceed `max_value`.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum OverflowBehaviour {
+		/// Fail with `StorageOverflow`, leaving the value unchanged.
+		Error,
+		/// Keep the value at `max_value`.
+		Saturate,
+		/// Restart from zero.
+		Wrap,
+	}
+
+	/// Limits of the pallet that can be changed through `set_parameters`.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct TemplateParameters {
+		/// The largest value that can be stored.
+		pub max_value: u32,
+		/// What happens when an increment would exceed `max_value`.
+		pub overflow: OverflowBehaviour,
+	}
+
+	impl Default for TemplateParameters {
+		fn default() -> Self {
+			Self { max_value: u32::MAX, overflow: OverflowBehaviour::Error }
+		}
+	}
+
+	/// The globally stored value, together with the block in which it last changed.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct SomethingRecord<BlockNumber> {
+		/// The stored value.
+		pub value: u32,
+		/// The block in which the value was last written.
+		pub updated_at: BlockNumber,
+	}
+
+	/// The progress of the offchain worker, persisted in the node's local offchain storage.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+	pub struct OffchainProgress<BlockNumber> {
+		/// The block in which the offchain worker last submitted a value.
+		pub last_submission: BlockNumber,
+		/// The number of values submitted so far.
+		pub submissions: u32,
+	}
+
+	/// The data of an unsigned submission, signed with the key of the offchain worker.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct ValuePayload<Public, BlockNumber> {
+		/// The block in which the value was computed.
+		pub block_number: BlockNumber,
+		/// The submitted value.
+		pub value: u32,
+		/// The key that signed the payload.
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for ValuePayload<T::Public, BlockNumberFor<T>> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// A change of `Something`, as kept in `History`.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ChangeRecord<AccountId, BlockNumber> {
+		/// The account that made the change.
+		pub who: AccountId,
+		/// The value before the change, if one was set.
+		pub old: Option<u32>,
+		/// The value after the change.
+		pub new: u32,
+		/// The block in which the change was made.
+		pub block_number: BlockNumber,
+	}
+
+	/// A value owned by an account, together with the deposit held for storing it.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct AccountValue<Balance> {
+		/// The value stored by the account.
+		pub value: u32,
+		/// The amount held from the account for this entry.
+		pub deposit: Balance,
+	}
+
 	// The pallet's runtime storage items.
 	// https://docs.substrate.io/v3/runtime/storage
 	#[pallet::storage]
 	#[pallet::getter(fn something)]
 	// Learn more about declaring storage items:
 	// https://docs.substrate.io/v3/runtime/storage#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	pub type Something<T: Config> = StorageValue<_, SomethingRecord<BlockNumberFor<T>>>;
+
+	/// The value stored by each account, along with the deposit held for it.
+	#[pallet::storage]
+	#[pallet::getter(fn account_value)]
+	pub type AccountValues<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, AccountValue<BalanceOf<T>>>;
+
+	/// The first block in which a new unsigned submission is accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	/// The last `MaxHistory` changes of `Something`, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> = StorageValue<
+		_,
+		BoundedVec<ChangeRecord<T::AccountId, BlockNumberFor<T>>, T::MaxHistory>,
+		ValueQuery,
+	>;
+
+	/// The limits currently applied to stored values.
+	#[pallet::storage]
+	#[pallet::getter(fn parameters)]
+	pub type Parameters<T: Config> = StorageValue<_, TemplateParameters, ValueQuery>;
+
+	/// The initial state of the pallet.
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial value of `Something`, if any.
+		pub something: Option<u32>,
+		/// The initial per-account values. A `SomethingDeposit` is held from each account, so
+		/// they must be endowed in the genesis of `Currency`.
+		pub account_values: Vec<(T::AccountId, u32)>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(value) = self.something {
+				<Something<T>>::put(SomethingRecord { value, updated_at: Zero::zero() });
+			}
+
+			let deposit = T::SomethingDeposit::get();
+			for (who, value) in &self.account_values {
+				assert!(
+					!<AccountValues<T>>::contains_key(who),
+					"duplicate account in the template pallet genesis config"
+				);
+				T::Currency::hold(&HoldReason::SomethingDeposit.into(), who, deposit)
+					.expect("accounts with a genesis value must be able to pay the deposit");
+				<AccountValues<T>>::insert(who, AccountValue { value: *value, deposit });
+			}
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [something, who]
-		SomethingStored(u32, T::AccountId),
+		/// `Something` was changed by `who` from `old` to `new`.
+		SomethingStored { who: T::AccountId, old: Option<u32>, new: u32 },
+		/// A deposit was held for a newly stored value.
+		DepositHeld { who: T::AccountId, deposit: BalanceOf<T> },
+		/// A stored value was removed and its deposit released.
+		SomethingCleared { who: T::AccountId, deposit: BalanceOf<T> },
+		/// The pallet's parameters were changed from `old` to `new`.
+		ParametersSet { old: TemplateParameters, new: TemplateParameters },
 	}
 
 	// Errors inform users that something went wrong.
@@ -54,10 +328,35 @@ pub mod pallet {
 		NoneValue,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
+		/// The account has no value stored.
+		NothingStored,
+		/// The value is larger than the `max_value` parameter.
+		ValueTooLarge,
+		/// `max_value` cannot be set below the current value of `Something`.
+		MaxValueBelowSomething,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Computes a new value every `SubmissionInterval` blocks and submits it, either through
+		/// a signed `do_something` transaction or, if `SubmitUnsigned` is set, through an
+		/// unsigned `submit_value_unsigned` transaction.
+		fn offchain_worker(block_number: BlockNumberFor<T>) {
+			let res = if T::SubmitUnsigned::get() {
+				Self::submit_value_unsigned_with_signed_payload(block_number)
+			} else {
+				Self::submit_value_signed(block_number)
+			};
+			if let Err(e) = res {
+				log::debug!(target: LOG_TARGET, "offchain worker did not submit a value: {}", e);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
+			Self::do_try_state()
+		}
+	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
@@ -66,41 +365,326 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
 		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
+		///
+		/// The value is also recorded under the signer's own entry. The first time an account
+		/// stores a value, `SomethingDeposit` is held from it until `clear_something` is called.
 		#[pallet::call_index(0)]
-		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::do_something(T::MaxHistory::get()))]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResultWithPostInfo {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://docs.substrate.io/v3/runtime/origins
 			let who = ensure_signed(origin)?;
+			ensure!(something <= Self::parameters().max_value, Error::<T>::ValueTooLarge);
 
-			// Update storage.
-			<Something<T>>::put(something);
+			// Update the signer's entry, holding the deposit if this is its first value.
+			<AccountValues<T>>::try_mutate(&who, |maybe_entry| -> DispatchResult {
+				let deposit = match maybe_entry {
+					Some(entry) => entry.deposit,
+					None => {
+						let deposit = T::SomethingDeposit::get();
+						T::Currency::hold(&HoldReason::SomethingDeposit.into(), &who, deposit)?;
+						Self::deposit_event(Event::DepositHeld { who: who.clone(), deposit });
+						deposit
+					},
+				};
+				*maybe_entry = Some(AccountValue { value: something, deposit });
+				Ok(())
+			})?;
 
-			// Emit an event.
-			Self::deposit_event(Event::SomethingStored(something, who));
+			// Update storage and emit an event.
+			Self::store_something(who, something);
 			// Return a successful DispatchResultWithPostInfo
 			Ok(().into())
 		}
 
 		/// An example dispatchable that may throw a custom error.
+		///
+		/// Increments the stored value. If that would exceed `max_value`, the outcome depends on
+		/// the `overflow` parameter.
 		#[pallet::call_index(1)]
-		#[pallet::weight(Weight::from_parts(10_000, 0) + T::DbWeight::get().reads_writes(1,1))]
+		#[pallet::weight(T::WeightInfo::cause_error(T::MaxHistory::get()))]
 		pub fn cause_error(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
 
 			// Read a value from storage.
 			match <Something<T>>::get() {
 				// Return an error if the value has not been set.
 				None => Err(Error::<T>::NoneValue)?,
 				Some(old) => {
-					// Increment the value read from storage; will error in the event of overflow.
-					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
+					// Increment the value read from storage, applying the overflow behaviour past
+					// `max_value`. Nothing is written on error, so only the cheaper path is charged.
+					let TemplateParameters { max_value, overflow } = Self::parameters();
+					let new = match old.value.checked_add(1).filter(|new| *new <= max_value) {
+						Some(new) => new,
+						None => match overflow {
+							OverflowBehaviour::Error => Err(Error::<T>::StorageOverflow
+								.with_weight(T::WeightInfo::cause_error_overflow()))?,
+							OverflowBehaviour::Saturate => max_value,
+							OverflowBehaviour::Wrap => 0,
+						},
+					};
 					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
+					Self::store_something(who, new);
 					Ok(().into())
 				},
 			}
 		}
+
+		/// Remove the value stored by the signer and release the deposit held for it.
+		#[pallet::call_index(2)]
+		#[pallet::weight(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let entry = <AccountValues<T>>::take(&who).ok_or(Error::<T>::NothingStored)?;
+			let released = T::Currency::release(
+				&HoldReason::SomethingDeposit.into(),
+				&who,
+				entry.deposit,
+				Precision::BestEffort,
+			)?;
+
+			Self::deposit_event(Event::SomethingCleared { who, deposit: released });
+			Ok(().into())
+		}
+
+		/// Store a value submitted by an offchain reporter through an unsigned transaction.
+		///
+		/// The payload must be signed by the key of one of the `AuthorizedReporters`; this is
+		/// checked by `validate_unsigned` before the transaction enters the pool. At most one
+		/// submission is accepted every `SubmissionInterval` blocks.
+		#[pallet::call_index(3)]
+		#[pallet::weight(T::WeightInfo::submit_value_unsigned(T::MaxHistory::get()))]
+		pub fn submit_value_unsigned(
+			origin: OriginFor<T>,
+			payload: ValuePayload<T::Public, BlockNumberFor<T>>,
+			_signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			ensure!(payload.value <= Self::parameters().max_value, Error::<T>::ValueTooLarge);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			<NextUnsignedAt<T>>::put(now.saturating_add(T::SubmissionInterval::get()));
+
+			Self::store_something(payload.public.into_account(), payload.value);
+			Ok(().into())
+		}
+
+		/// Change the limits applied to stored values.
+		///
+		/// The origin must be `AdminOrigin`. `max_value` cannot be lowered below the current value
+		/// of `Something`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::set_parameters())]
+		pub fn set_parameters(
+			origin: OriginFor<T>,
+			parameters: TemplateParameters,
+		) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(
+				<Something<T>>::get().map_or(true, |record| record.value <= parameters.max_value),
+				Error::<T>::MaxValueBelowSomething
+			);
+
+			let old = <Parameters<T>>::mutate(|current| sp_std::mem::replace(current, parameters));
+			Self::deposit_event(Event::ParametersSet { old, new: parameters });
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::submit_value_unsigned { payload, signature } = call else {
+				return InvalidTransaction::Call.into()
+			};
+
+			let signature_valid =
+				SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+			if !signature_valid {
+				return InvalidTransaction::BadProof.into()
+			}
+			if !T::AuthorizedReporters::contains(&payload.public.clone().into_account()) {
+				return InvalidTransaction::BadSigner.into()
+			}
+			if payload.value > Self::parameters().max_value {
+				return InvalidTransaction::Call.into()
+			}
+
+			// Reject submissions before the next accepted block, and those computed in the future.
+			let next_unsigned_at = <NextUnsignedAt<T>>::get();
+			if next_unsigned_at > payload.block_number {
+				return InvalidTransaction::Stale.into()
+			}
+			if frame_system::Pallet::<T>::block_number() < payload.block_number {
+				return InvalidTransaction::Future.into()
+			}
+
+			ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+				.priority(T::UnsignedPriority::get())
+				// Only one submission is accepted per slot; others compete on priority.
+				.and_provides(next_unsigned_at)
+				.longevity(T::SubmissionInterval::get().saturated_into::<u64>())
+				.propagate(true)
+				.build()
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Set `Something` to `new` on behalf of `who`, recording the change in `History` and
+		/// emitting `SomethingStored`.
+		fn store_something(who: T::AccountId, new: u32) {
+			let block_number = frame_system::Pallet::<T>::block_number();
+			let old = <Something<T>>::get().map(|record| record.value);
+			<Something<T>>::put(SomethingRecord { value: new, updated_at: block_number });
+
+			<History<T>>::mutate(|history| {
+				// Once full, the oldest change is dropped to make room for the new one.
+				let change = ChangeRecord { who: who.clone(), old, new, block_number };
+				let _ = history.force_insert_keep_right(history.len(), change);
+			});
+
+			Self::deposit_event(Event::SomethingStored { who, old, new });
+		}
+
+		/// Record in local offchain storage that a value is submitted in `block_number`, and
+		/// return the value to submit.
+		///
+		/// Fails if a value was submitted less than `SubmissionInterval` blocks ago, or if another
+		/// offchain worker run is updating the progress concurrently.
+		fn next_offchain_value(block_number: BlockNumberFor<T>) -> Result<u32, &'static str> {
+			const RECENTLY_SUBMITTED: () = ();
+
+			let progress = StorageValueRef::persistent(OFFCHAIN_PROGRESS_KEY);
+			let interval = T::SubmissionInterval::get();
+			let res = progress.mutate(
+				|last: Result<Option<OffchainProgress<_>>, StorageRetrievalError>| match last {
+					Ok(Some(last))
+						if block_number < last.last_submission.saturating_add(interval) =>
+						Err(RECENTLY_SUBMITTED),
+					Ok(Some(last)) => Ok(OffchainProgress {
+						last_submission: block_number,
+						submissions: last.submissions.saturating_add(1),
+					}),
+					_ => Ok(OffchainProgress { last_submission: block_number, submissions: 1 }),
+				},
+			);
+
+			match res {
+				Ok(progress) => Ok(progress.submissions),
+				Err(MutateStorageError::ValueFunctionFailed(RECENTLY_SUBMITTED)) =>
+					Err("a value was submitted recently"),
+				Err(MutateStorageError::ConcurrentModification(_)) =>
+					Err("the offchain progress is being updated by another run"),
+			}
+		}
+
+		/// Compute the next value off-chain and submit it with any available local key.
+		fn submit_value_signed(block_number: BlockNumberFor<T>) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no local account available; insert one with the `author_insertKey` RPC")
+			}
+
+			let value = Self::next_offchain_value(block_number)?;
+			match signer.send_signed_transaction(|_account| Call::do_something { something: value })
+			{
+				Some((account, Ok(()))) => {
+					log::info!(
+						target: LOG_TARGET,
+						"[{:?}] submitted value {} from {:?}",
+						block_number,
+						value,
+						account.id
+					);
+					Ok(())
+				},
+				Some((_, Err(()))) => Err("failed to submit the signed transaction"),
+				None => Err("no local account available"),
+			}
+		}
+
+		/// Compute the next value off-chain and submit it through an unsigned transaction whose
+		/// payload is signed with any available local key.
+		fn submit_value_unsigned_with_signed_payload(
+			block_number: BlockNumberFor<T>,
+		) -> Result<(), &'static str> {
+			if <NextUnsignedAt<T>>::get() > block_number {
+				return Err("too early to submit an unsigned transaction")
+			}
+
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err("no local account available; insert one with the `author_insertKey` RPC")
+			}
+
+			let value = Self::next_offchain_value(block_number)?;
+			match signer.send_unsigned_transaction(
+				|account| ValuePayload { block_number, value, public: account.public.clone() },
+				|payload, signature| Call::submit_value_unsigned { payload, signature },
+			) {
+				Some((_, Ok(()))) => {
+					log::info!(
+						target: LOG_TARGET,
+						"[{:?}] submitted value {} unsigned",
+						block_number,
+						value
+					);
+					Ok(())
+				},
+				Some((_, Err(()))) => Err("failed to submit the unsigned transaction"),
+				None => Err("no local account available"),
+			}
+		}
+
+		/// Ensure the invariants of the pallet hold:
+		///
+		/// * every entry in `AccountValues` is backed by exactly its recorded deposit on hold;
+		/// * the deposits held by the pallet over all accounts add up to those of the entries, so
+		///   no deposit is left on hold without an entry;
+		/// * `Something` was not written in a block after the current one;
+		/// * `Something` is at most `max_value`: every write keeps it within it, whatever the
+		///   `overflow` behaviour, and `set_parameters` does not lower `max_value` below it.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), TryRuntimeError> {
+			let reason = HoldReason::SomethingDeposit.into();
+			let mut recorded = BalanceOf::<T>::zero();
+			for (who, entry) in AccountValues::<T>::iter() {
+				let held =
+					<T::Currency as fungible::InspectHold<_>>::balance_on_hold(&reason, &who);
+				ensure!(
+					held == entry.deposit,
+					"the held deposit must match the deposit recorded for the entry"
+				);
+				recorded.saturating_accrue(entry.deposit);
+			}
+
+			// The accounts of `Currency` are those of `frame_system`, as required by `Config`.
+			let held = frame_system::Account::<T>::iter_keys()
+				.map(|who| {
+					<T::Currency as fungible::InspectHold<_>>::balance_on_hold(&reason, &who)
+				})
+				.fold(BalanceOf::<T>::zero(), |total, held| total.saturating_add(held));
+			ensure!(
+				held == recorded,
+				"the deposits held over all accounts must add up to those of the entries"
+			);
+
+			if let Some(record) = Something::<T>::get() {
+				ensure!(
+					record.updated_at <= frame_system::Pallet::<T>::block_number(),
+					"`Something` cannot have been written in a future block"
+				);
+				ensure!(
+					record.value <= Self::parameters().max_value,
+					"`Something` cannot be larger than `max_value`"
+				);
+			}
+
+			Ok(())
+		}
 	}
 }
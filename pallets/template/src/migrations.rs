@@ -0,0 +1,83 @@
+//! Storage migrations for the template pallet.
+
+/// Migrations to storage version 1, which turns `Something` into a [`crate::SomethingRecord`].
+pub mod v1 {
+	use crate::{Config, Pallet, Something, SomethingRecord, LOG_TARGET};
+	use frame_support::{
+		pallet_prelude::*,
+		storage_alias,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	};
+	#[cfg(feature = "try-runtime")]
+	use sp_runtime::TryRuntimeError;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	/// The storage layout before version 1.
+	mod v0 {
+		use super::*;
+
+		#[storage_alias]
+		pub type Something<T: Config> = StorageValue<Pallet<T>, u32>;
+	}
+
+	/// Wraps the `u32` stored in `Something` into a [`SomethingRecord`], stamped with the block
+	/// in which the migration runs.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Pallet::<T>::on_chain_storage_version();
+			if on_chain_version != 0 {
+				log::info!(
+					target: LOG_TARGET,
+					"skipping v1 migration: on-chain storage version is {:?}",
+					on_chain_version
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let updated_at = frame_system::Pallet::<T>::block_number();
+			let translated = Something::<T>::translate::<u32, _>(|maybe_value| {
+				maybe_value.map(|value| SomethingRecord { value, updated_at })
+			});
+			if translated.is_err() {
+				log::error!(target: LOG_TARGET, "failed to decode the v0 value of `Something`");
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			log::info!(target: LOG_TARGET, "migrated to storage version 1");
+
+			T::DbWeight::get().reads_writes(3, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+			// Once migrated, `Something` holds a record, which must not be read as a `u32`: the
+			// migration is skipped, and there is no old value to compare with.
+			if Pallet::<T>::on_chain_storage_version() != 0 {
+				return Ok(Vec::new())
+			}
+			Ok(v0::Something::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+			ensure!(
+				Pallet::<T>::on_chain_storage_version() == 1,
+				"the storage version must be 1 after the migration"
+			);
+			if state.is_empty() {
+				return Ok(())
+			}
+
+			let old_value = Option::<u32>::decode(&mut &state[..])
+				.map_err(|_| "the pre-upgrade state must decode")?;
+			ensure!(
+				Something::<T>::get().map(|record| record.value) == old_value,
+				"`Something` must keep its value across the migration"
+			);
+			Ok(())
+		}
+	}
+}
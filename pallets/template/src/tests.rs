@@ -1,5 +1,22 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	migrations, mock::*, AccountValue, AccountValues, ChangeRecord, Error, Event, HoldReason,
+	NextUnsignedAt, OverflowBehaviour, Parameters, Something, SomethingRecord, TemplateParameters,
+	ValuePayload, WeightInfo,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{fungible::InspectHold, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+};
+use sp_core::offchain::{
+	testing::{TestOffchainExt, TestTransactionPoolExt},
+	OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
 
 #[test]
 fn it_works_for_default_value() {
@@ -7,7 +24,7 @@ fn it_works_for_default_value() {
 		// Dispatch a signed extrinsic.
 		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(42));
 	});
 }
 
@@ -21,3 +38,543 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn overflow_error_refunds_unused_weight() {
+	new_test_ext().execute_with(|| {
+		Something::<Test>::put(SomethingRecord { value: u32::MAX, updated_at: 0 });
+
+		let err = TemplateModule::cause_error(RuntimeOrigin::signed(1)).unwrap_err();
+		assert_eq!(err.error, Error::<Test>::StorageOverflow.into());
+		assert_eq!(err.post_info.actual_weight, Some(<() as WeightInfo>::cause_error_overflow()));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(u32::MAX));
+	});
+}
+
+#[test]
+fn do_something_holds_deposit_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		assert_eq!(
+			TemplateModule::account_value(1),
+			Some(AccountValue { value: 42, deposit: SomethingDeposit::get() })
+		);
+		assert_eq!(held(1), SomethingDeposit::get());
+
+		// Overwriting the value does not hold a second deposit.
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 7));
+		assert_eq!(TemplateModule::account_value(1).map(|entry| entry.value), Some(7));
+		assert_eq!(held(1), SomethingDeposit::get());
+		assert_eq!(Balances::free_balance(1), 100 - SomethingDeposit::get());
+	});
+}
+
+#[test]
+fn accounts_own_their_values() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 1));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 2));
+
+		assert_eq!(TemplateModule::account_value(1).map(|entry| entry.value), Some(1));
+		assert_eq!(TemplateModule::account_value(2).map(|entry| entry.value), Some(2));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(2));
+	});
+}
+
+#[test]
+fn do_something_fails_without_funds_for_deposit() {
+	new_test_ext().execute_with(|| {
+		assert!(TemplateModule::do_something(RuntimeOrigin::signed(3), 42).is_err());
+		assert_eq!(TemplateModule::account_value(3), None);
+		assert_eq!(TemplateModule::something(), None);
+	});
+}
+
+#[test]
+fn clear_something_releases_deposit() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::account_value(1), None);
+		assert_eq!(held(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+		System::assert_last_event(
+			Event::SomethingCleared { who: 1, deposit: SomethingDeposit::get() }.into(),
+		);
+	});
+}
+
+#[test]
+fn clear_something_fails_without_value() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::clear_something(RuntimeOrigin::signed(1)),
+			Error::<Test>::NothingStored
+		);
+	});
+}
+
+#[test]
+fn cause_error_records_update_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 1));
+
+		System::set_block_number(5);
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(2)));
+		assert_eq!(TemplateModule::something(), Some(SomethingRecord { value: 2, updated_at: 5 }));
+	});
+}
+
+#[test]
+fn migration_to_v1_wraps_value() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(10);
+		StorageVersion::new(0).put::<TemplateModule>();
+		frame_support::storage::unhashed::put(&Something::<Test>::hashed_key(), &7u32);
+
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TemplateModule::something(), Some(SomethingRecord { value: 7, updated_at: 10 }));
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+	});
+}
+
+#[test]
+fn migration_to_v1_is_idempotent() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<TemplateModule>();
+		Something::<Test>::put(SomethingRecord { value: 7, updated_at: 1 });
+
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		assert_eq!(TemplateModule::something(), Some(SomethingRecord { value: 7, updated_at: 1 }));
+	});
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn migration_to_v1_checks_skip_migrated_storage() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<TemplateModule>();
+		Something::<Test>::put(SomethingRecord { value: 7, updated_at: 1 });
+
+		let state = migrations::v1::MigrateToV1::<Test>::pre_upgrade().unwrap();
+		assert!(state.is_empty());
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_ok!(migrations::v1::MigrateToV1::<Test>::post_upgrade(state));
+	});
+}
+
+#[test]
+fn try_state_passes_after_storing_and_clearing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 1));
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 2));
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+		assert_ok!(TemplateModule::do_try_state());
+	});
+}
+
+#[test]
+fn try_state_detects_unbacked_entry() {
+	new_test_ext().execute_with(|| {
+		AccountValues::<Test>::insert(
+			2,
+			AccountValue { value: 1, deposit: SomethingDeposit::get() },
+		);
+		assert!(TemplateModule::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn try_state_detects_deposit_held_without_entry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 1));
+		AccountValues::<Test>::remove(1);
+		assert!(TemplateModule::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn try_state_detects_value_above_max_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 10));
+		Parameters::<Test>::put(TemplateParameters {
+			max_value: 9,
+			overflow: OverflowBehaviour::Saturate,
+		});
+		assert!(TemplateModule::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn try_state_detects_update_in_future_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		Something::<Test>::put(SomethingRecord { value: 1, updated_at: 2 });
+		assert!(TemplateModule::do_try_state().is_err());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_signed_values() {
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![1]);
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(1);
+		// Nothing is submitted within `SubmissionInterval` of the last submission.
+		TemplateModule::offchain_worker(3);
+		TemplateModule::offchain_worker(6);
+
+		let calls: Vec<_> = pool_state
+			.write()
+			.transactions
+			.drain(..)
+			.map(|tx| Extrinsic::decode(&mut &*tx).unwrap())
+			.inspect(|tx| assert_eq!(tx.signature, Some((0, ()))))
+			.map(|tx| tx.call)
+			.collect();
+		assert_eq!(
+			calls,
+			vec![
+				RuntimeCall::TemplateModule(crate::Call::do_something { something: 1 }),
+				RuntimeCall::TemplateModule(crate::Call::do_something { something: 2 }),
+			]
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_needs_a_local_key() {
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+
+	t.execute_with(|| {
+		TemplateModule::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn genesis_config_seeds_values_and_holds_deposits() {
+	new_test_ext_with_values(Some(7), vec![(1, 10), (2, 20)]).execute_with(|| {
+		assert_eq!(TemplateModule::something(), Some(SomethingRecord { value: 7, updated_at: 0 }));
+		assert_eq!(AccountValues::<Test>::get(1), Some(AccountValue { value: 10, deposit: 10 }));
+		assert_eq!(AccountValues::<Test>::get(2), Some(AccountValue { value: 20, deposit: 10 }));
+		assert_eq!(held(1), 10);
+		assert_eq!(held(2), 10);
+		assert_ok!(TemplateModule::do_try_state());
+
+		// Genesis entries behave like any other entry.
+		assert_ok!(TemplateModule::clear_something(RuntimeOrigin::signed(1)));
+		assert_eq!(held(1), 0);
+	});
+}
+
+#[test]
+#[should_panic(expected = "duplicate account in the template pallet genesis config")]
+fn genesis_config_rejects_duplicate_accounts() {
+	new_test_ext_with_values(None, vec![(1, 10), (1, 20)]);
+}
+
+#[test]
+#[should_panic(expected = "accounts with a genesis value must be able to pay the deposit")]
+fn genesis_config_rejects_accounts_without_funds() {
+	new_test_ext_with_values(None, vec![(3, 10)]);
+}
+
+/// Build a `submit_value_unsigned` call for `value`, signed by the test key `key`.
+fn unsigned_call(key: u64, block_number: u64, value: u32) -> crate::Call<Test> {
+	let payload = ValuePayload { block_number, value, public: UintAuthorityId(key) };
+	let signature = TestSignature(key, payload.encode());
+	crate::Call::submit_value_unsigned { payload, signature }
+}
+
+#[test]
+fn validate_unsigned_accepts_signed_payload() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let valid = TemplateModule::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_call(1, 3, 42),
+		)
+		.unwrap();
+		assert_eq!(valid.priority, 1 << 20);
+		assert_eq!(valid.longevity, 5);
+		assert_eq!(valid.provides, vec![("TemplateOffchainWorker", 0u64).encode()]);
+		assert!(valid.propagate);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_bad_signature() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let mut call = unsigned_call(1, 3, 42);
+		if let crate::Call::submit_value_unsigned { ref mut payload, .. } = call {
+			payload.value = 43;
+		}
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_unauthorized_reporter() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		// Key 2 signs its payload correctly, but is not one of the authorized reporters.
+		assert_eq!(
+			TemplateModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_call(2, 3, 42)
+			),
+			InvalidTransaction::BadSigner.into()
+		);
+
+		Reporters::set(vec![1, 2]);
+		assert_ok!(TemplateModule::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_call(2, 3, 42)
+		));
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_stale_and_future_payloads() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_eq!(
+			TemplateModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_call(1, 4, 42)
+			),
+			InvalidTransaction::Future.into()
+		);
+
+		assert_ok!(TemplateModule::submit_value_unsigned(
+			RuntimeOrigin::none(),
+			ValuePayload { block_number: 3, value: 42, public: UintAuthorityId(1) },
+			TestSignature(1, vec![]),
+		));
+		assert_eq!(NextUnsignedAt::<Test>::get(), 8);
+
+		System::set_block_number(10);
+		assert_eq!(
+			TemplateModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_call(1, 7, 42)
+			),
+			InvalidTransaction::Stale.into()
+		);
+		assert_ok!(TemplateModule::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_call(1, 8, 42)
+		));
+	});
+}
+
+#[test]
+fn submit_value_unsigned_requires_none_origin() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let payload = ValuePayload { block_number: 1, value: 42, public: UintAuthorityId(2) };
+		assert_noop!(
+			TemplateModule::submit_value_unsigned(
+				RuntimeOrigin::signed(1),
+				payload.clone(),
+				TestSignature(2, payload.encode()),
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(TemplateModule::submit_value_unsigned(
+			RuntimeOrigin::none(),
+			payload.clone(),
+			TestSignature(2, payload.encode()),
+		));
+		assert_eq!(TemplateModule::something(), Some(SomethingRecord { value: 42, updated_at: 1 }));
+		// No deposit is held for unsigned submissions.
+		assert_eq!(AccountValues::<Test>::get(2), None);
+		System::assert_last_event(Event::SomethingStored { who: 2, old: None, new: 42 }.into());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_values() {
+	let (offchain, _) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainDbExt::new(offchain.clone()));
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![1]);
+	SubmitUnsigned::set(true);
+
+	t.execute_with(|| {
+		System::set_block_number(1);
+		TemplateModule::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		let RuntimeCall::TemplateModule(call) = tx.call else { panic!("unexpected call") };
+		assert_eq!(call, unsigned_call(1, 1, 1));
+		assert_ok!(TemplateModule::validate_unsigned(TransactionSource::Local, &call));
+	});
+}
+
+#[test]
+fn set_parameters_requires_admin_origin() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let parameters = TemplateParameters { max_value: 10, overflow: OverflowBehaviour::Wrap };
+		assert_noop!(
+			TemplateModule::set_parameters(RuntimeOrigin::signed(1), parameters),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(TemplateModule::set_parameters(RuntimeOrigin::root(), parameters));
+		assert_eq!(Parameters::<Test>::get(), parameters);
+		System::assert_last_event(
+			Event::ParametersSet { old: TemplateParameters::default(), new: parameters }.into(),
+		);
+	});
+}
+
+#[test]
+fn max_value_limits_stored_values() {
+	new_test_ext().execute_with(|| {
+		let parameters = TemplateParameters { max_value: 10, overflow: OverflowBehaviour::Error };
+		assert_ok!(TemplateModule::set_parameters(RuntimeOrigin::root(), parameters));
+
+		assert_noop!(
+			TemplateModule::do_something(RuntimeOrigin::signed(1), 11),
+			Error::<Test>::ValueTooLarge
+		);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 10));
+		assert_noop!(
+			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
+			Error::<Test>::StorageOverflow
+		);
+		assert_eq!(
+			TemplateModule::validate_unsigned(
+				TransactionSource::External,
+				&unsigned_call(1, 0, 11)
+			),
+			InvalidTransaction::Call.into()
+		);
+	});
+}
+
+#[test]
+fn max_value_cannot_be_set_below_something() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 10));
+
+		let parameters = TemplateParameters { max_value: 9, overflow: OverflowBehaviour::Wrap };
+		assert_noop!(
+			TemplateModule::set_parameters(RuntimeOrigin::root(), parameters),
+			Error::<Test>::MaxValueBelowSomething
+		);
+
+		let parameters = TemplateParameters { max_value: 10, overflow: OverflowBehaviour::Wrap };
+		assert_ok!(TemplateModule::set_parameters(RuntimeOrigin::root(), parameters));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_ok!(TemplateModule::do_try_state());
+	});
+}
+
+#[test]
+fn overflow_behaviour_applies_past_max_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 10));
+
+		let saturate = TemplateParameters { max_value: 10, overflow: OverflowBehaviour::Saturate };
+		assert_ok!(TemplateModule::set_parameters(RuntimeOrigin::root(), saturate));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(10));
+
+		let wrap = TemplateParameters { max_value: 10, overflow: OverflowBehaviour::Wrap };
+		assert_ok!(TemplateModule::set_parameters(RuntimeOrigin::root(), wrap));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(0));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_eq!(TemplateModule::something().map(|record| record.value), Some(1));
+	});
+}
+
+#[test]
+fn changes_emit_old_and_new_values() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 42));
+		System::assert_last_event(Event::SomethingStored { who: 1, old: None, new: 42 }.into());
+
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(2)));
+		System::assert_last_event(Event::SomethingStored { who: 2, old: Some(42), new: 43 }.into());
+	});
+}
+
+#[test]
+fn history_keeps_the_most_recent_changes() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(1), 1));
+		System::set_block_number(2);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 2));
+		assert_ok!(TemplateModule::cause_error(RuntimeOrigin::signed(1)));
+		assert_eq!(
+			TemplateModule::history().into_inner(),
+			vec![
+				ChangeRecord { who: 1, old: None, new: 1, block_number: 1 },
+				ChangeRecord { who: 2, old: Some(1), new: 2, block_number: 2 },
+				ChangeRecord { who: 1, old: Some(2), new: 3, block_number: 2 },
+			]
+		);
+
+		// Once `MaxHistory` changes are kept, the oldest one is dropped.
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::do_something(RuntimeOrigin::signed(2), 4));
+		assert_eq!(
+			TemplateModule::history().into_inner(),
+			vec![
+				ChangeRecord { who: 2, old: Some(1), new: 2, block_number: 2 },
+				ChangeRecord { who: 1, old: Some(2), new: 3, block_number: 2 },
+				ChangeRecord { who: 2, old: Some(3), new: 4, block_number: 3 },
+			]
+		);
+	});
+}
+
+#[test]
+fn failed_changes_are_not_recorded() {
+	new_test_ext().execute_with(|| {
+		Something::<Test>::put(SomethingRecord { value: u32::MAX, updated_at: 0 });
+		assert_noop!(
+			TemplateModule::cause_error(RuntimeOrigin::signed(1)),
+			Error::<Test>::StorageOverflow
+		);
+		assert!(TemplateModule::history().is_empty());
+	});
+}
+
+fn held(who: u64) -> u64 {
+	Balances::balance_on_hold(&HoldReason::SomethingDeposit.into(), &who)
+}
@@ -0,0 +1,154 @@
+//! Weights for pallet_parachain_template
+//!
+//! These are hand-estimated placeholders, not benchmark output: each is a rough execution time
+//! plus the storage accesses listed above it. Where `History` is touched, the weight grows with
+//! its length `h`, by the 45 bytes of proof of a `ChangeRecord`. Regenerate this file from the pallet's benchmarks
+//! on reference hardware before relying on it on a live chain:
+//!
+//! ```text
+//! ./target/release/parachain-template-node benchmark pallet --chain dev \
+//! 	--pallet pallet_parachain_template --extrinsic '*' --steps 50 --repeat 20 \
+//! 	--output pallets/template/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_parachain_template.
+pub trait WeightInfo {
+	fn do_something(h: u32, ) -> Weight;
+	fn cause_error(h: u32, ) -> Weight;
+	fn cause_error_overflow() -> Weight;
+	fn clear_something() -> Weight;
+	fn submit_value_unsigned(h: u32, ) -> Weight;
+	fn set_parameters() -> Weight;
+}
+
+/// Placeholder weights for pallet_parachain_template, until it is benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet AccountValues (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn do_something(h: u32, ) -> Weight {
+		Weight::from_parts(47_000_000, 3593)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn cause_error(h: u32, ) -> Weight {
+		Weight::from_parts(9_000_000, 1493)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:0)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	fn cause_error_overflow() -> Weight {
+		Weight::from_parts(5_000_000, 1493)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+	}
+	/// Storage: TemplatePallet AccountValues (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn clear_something() -> Weight {
+		Weight::from_parts(38_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplatePallet NextUnsignedAt (r:0 w:1)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn submit_value_unsigned(h: u32, ) -> Weight {
+		Weight::from_parts(14_000_000, 1493)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:0)
+	/// Storage: TemplatePallet Parameters (r:1 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(9_000_000, 1493)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet AccountValues (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn do_something(h: u32, ) -> Weight {
+		Weight::from_parts(47_000_000, 3593)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn cause_error(h: u32, ) -> Weight {
+		Weight::from_parts(9_000_000, 1493)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:0)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	fn cause_error_overflow() -> Weight {
+		Weight::from_parts(5_000_000, 1493)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+	}
+	/// Storage: TemplatePallet AccountValues (r:1 w:1)
+	/// Storage: Balances Holds (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn clear_something() -> Weight {
+		Weight::from_parts(38_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: TemplatePallet NextUnsignedAt (r:0 w:1)
+	/// Storage: TemplatePallet Parameters (r:1 w:0)
+	/// Storage: TemplatePallet History (r:1 w:1)
+	/// Storage: TemplatePallet Something (r:1 w:1)
+	/// The range of component `h` is `[0, 100]`.
+	fn submit_value_unsigned(h: u32, ) -> Weight {
+		Weight::from_parts(14_000_000, 1493)
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(h.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 45).saturating_mul(h.into()))
+	}
+	/// Storage: TemplatePallet Something (r:1 w:0)
+	/// Storage: TemplatePallet Parameters (r:1 w:1)
+	fn set_parameters() -> Weight {
+		Weight::from_parts(9_000_000, 1493)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
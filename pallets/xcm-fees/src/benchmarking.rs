@@ -0,0 +1,22 @@
+//! Benchmarking setup for pallet-parachain-xcm-fees
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as XcmFees;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite, BenchmarkError};
+use sp_std::boxed::Box;
+use xcm::{latest::prelude::*, VersionedMultiLocation};
+
+benchmarks! {
+	set_units_per_second {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let location = MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(1)));
+	}: _<T::RuntimeOrigin>(origin, Box::new(VersionedMultiLocation::V3(location)), Some(1_000_000))
+	verify {
+		assert_eq!(UnitsPerSecond::<T>::get(location), Some(1_000_000));
+	}
+}
+
+impl_benchmark_test_suite!(XcmFees, crate::mock::new_test_ext(), crate::mock::Test,);
@@ -0,0 +1,93 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Rates at which XCM execution can be paid for in assets other than the relay chain token.
+//!
+//! Governance sets, per asset location, how many units of the asset buy one second of weight.
+//! [`ForeignAssetTrader`] then accepts those assets as payment in `BuyExecution`.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+mod trader;
+pub mod weights;
+pub use trader::ForeignAssetTrader;
+pub use weights::*;
+
+/// The log target of this pallet.
+pub const LOG_TARGET: &str = "runtime::xcm-fees";
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::WeightInfo;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::boxed::Box;
+	use xcm::{latest::MultiLocation, VersionedMultiLocation};
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to set the rate of an asset.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The units of an asset, by location, that buy one second of execution weight.
+	///
+	/// Assets without an entry cannot be used to pay for XCM execution.
+	#[pallet::storage]
+	#[pallet::getter(fn units_per_second)]
+	pub type UnitsPerSecond<T: Config> = StorageMap<_, Blake2_128Concat, MultiLocation, u128>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The rate of the asset at `location` was set, or removed if `None`.
+		UnitsPerSecondSet { location: MultiLocation, units_per_second: Option<u128> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The location could not be converted into the latest XCM version.
+		BadVersion,
+		/// A rate of zero would make execution free; remove the rate instead.
+		ZeroRate,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the units of the asset at `location` that buy one second of execution weight, or
+		/// stop accepting the asset if `units_per_second` is `None`.
+		///
+		/// The origin must be `AdminOrigin`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_units_per_second())]
+		pub fn set_units_per_second(
+			origin: OriginFor<T>,
+			location: Box<VersionedMultiLocation>,
+			units_per_second: Option<u128>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let location: MultiLocation =
+				(*location).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			ensure!(units_per_second != Some(0), Error::<T>::ZeroRate);
+
+			<UnitsPerSecond<T>>::set(location, units_per_second);
+			Self::deposit_event(Event::UnitsPerSecondSet { location, units_per_second });
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,166 @@
+use crate::{mock::*, Error, Event, UnitsPerSecond};
+use frame_support::{
+	assert_noop, assert_ok,
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
+};
+use sp_runtime::DispatchError;
+use xcm::{latest::prelude::*, VersionedMultiLocation};
+use xcm_executor::{traits::WeightTrader, Assets};
+
+/// An asset of the sibling parachain 1000.
+fn sibling_asset() -> MultiLocation {
+	MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(1)))
+}
+
+fn set_rate(location: MultiLocation, units_per_second: Option<u128>) {
+	assert_ok!(XcmFees::set_units_per_second(
+		RuntimeOrigin::root(),
+		Box::new(VersionedMultiLocation::V3(location)),
+		units_per_second,
+	));
+}
+
+fn context() -> XcmContext {
+	XcmContext { origin: None, message_id: [0; 32], topic: None }
+}
+
+/// A quarter of a second of reference time.
+fn quarter_second() -> Weight {
+	Weight::from_parts(WEIGHT_REF_TIME_PER_SECOND / 4, 0)
+}
+
+#[test]
+fn admin_sets_and_removes_rate() {
+	new_test_ext().execute_with(|| {
+		set_rate(sibling_asset(), Some(1_000));
+		assert_eq!(XcmFees::units_per_second(sibling_asset()), Some(1_000));
+		System::assert_last_event(
+			Event::UnitsPerSecondSet { location: sibling_asset(), units_per_second: Some(1_000) }
+				.into(),
+		);
+
+		set_rate(sibling_asset(), None);
+		assert!(!UnitsPerSecond::<Test>::contains_key(sibling_asset()));
+		System::assert_last_event(
+			Event::UnitsPerSecondSet { location: sibling_asset(), units_per_second: None }.into(),
+		);
+	});
+}
+
+#[test]
+fn set_rate_requires_admin_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmFees::set_units_per_second(
+				RuntimeOrigin::signed(1),
+				Box::new(VersionedMultiLocation::V3(sibling_asset())),
+				Some(1_000),
+			),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn zero_rate_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmFees::set_units_per_second(
+				RuntimeOrigin::root(),
+				Box::new(VersionedMultiLocation::V3(sibling_asset())),
+				Some(0),
+			),
+			Error::<Test>::ZeroRate
+		);
+	});
+}
+
+#[test]
+fn trader_charges_assets_with_a_rate() {
+	new_test_ext().execute_with(|| {
+		set_rate(sibling_asset(), Some(1_000));
+
+		let mut trader = Trader::new();
+		let payment: Assets = MultiAsset::from((sibling_asset(), 400u128)).into();
+		let unused = trader.buy_weight(quarter_second(), payment, &context()).unwrap();
+		assert_eq!(unused, MultiAsset::from((sibling_asset(), 150u128)).into());
+
+		drop(trader);
+		assert_eq!(Revenue::get(), vec![(sibling_asset(), 250u128).into()]);
+	});
+}
+
+#[test]
+fn trader_rejects_assets_without_a_rate() {
+	new_test_ext().execute_with(|| {
+		let mut trader = Trader::new();
+		let payment: Assets = MultiAsset::from((sibling_asset(), 400u128)).into();
+		assert_eq!(
+			trader.buy_weight(quarter_second(), payment, &context()),
+			Err(XcmError::TooExpensive)
+		);
+
+		drop(trader);
+		assert!(Revenue::get().is_empty());
+	});
+}
+
+#[test]
+fn trader_rejects_insufficient_payment() {
+	new_test_ext().execute_with(|| {
+		set_rate(sibling_asset(), Some(1_000));
+
+		let mut trader = Trader::new();
+		let payment: Assets = MultiAsset::from((sibling_asset(), 249u128)).into();
+		assert_eq!(
+			trader.buy_weight(quarter_second(), payment, &context()),
+			Err(XcmError::TooExpensive)
+		);
+	});
+}
+
+#[test]
+fn trader_refunds_unused_weight() {
+	new_test_ext().execute_with(|| {
+		set_rate(sibling_asset(), Some(1_000));
+
+		let mut trader = Trader::new();
+		let payment: Assets = MultiAsset::from((sibling_asset(), 500u128)).into();
+		assert_ok!(trader.buy_weight(quarter_second() * 2, payment, &context()));
+		assert_eq!(
+			trader.refund_weight(quarter_second(), &context()),
+			Some((sibling_asset(), 250u128).into())
+		);
+		// Never refund more weight than was bought.
+		assert_eq!(
+			trader.refund_weight(quarter_second() * 4, &context()),
+			Some((sibling_asset(), 250u128).into())
+		);
+		assert_eq!(trader.refund_weight(quarter_second(), &context()), None);
+
+		drop(trader);
+		assert!(Revenue::get().is_empty());
+	});
+}
+
+#[test]
+fn later_purchases_use_the_first_asset() {
+	new_test_ext().execute_with(|| {
+		let other = MultiLocation::new(1, X2(Parachain(2000), GeneralIndex(7)));
+		set_rate(sibling_asset(), Some(1_000));
+		set_rate(other, Some(4_000));
+
+		let mut trader = Trader::new();
+		let payment: Assets = MultiAsset::from((sibling_asset(), 250u128)).into();
+		assert_ok!(trader.buy_weight(quarter_second(), payment, &context()));
+
+		let payment: Assets = MultiAsset::from((other, 1_000u128)).into();
+		assert_eq!(
+			trader.buy_weight(quarter_second(), payment, &context()),
+			Err(XcmError::TooExpensive)
+		);
+
+		drop(trader);
+		assert_eq!(Revenue::get(), vec![(sibling_asset(), 250u128).into()]);
+	});
+}
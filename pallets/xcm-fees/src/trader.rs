@@ -0,0 +1,93 @@
+//! An XCM weight trader accepting the assets that have a rate in [`UnitsPerSecond`].
+
+use crate::{Config, Pallet, LOG_TARGET};
+use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight};
+use sp_std::marker::PhantomData;
+use xcm::latest::prelude::*;
+use xcm_executor::{
+	traits::{TakeRevenue, WeightTrader},
+	Assets,
+};
+
+#[cfg(doc)]
+use crate::UnitsPerSecond;
+
+/// Buys weight with the first asset of the payment that has a rate in [`UnitsPerSecond`].
+///
+/// Only the reference time of the weight is charged for. Once an asset has been used, further
+/// purchases by the same trader must use that asset too. Whatever was not refunded is passed to
+/// `R` when the trader is dropped.
+pub struct ForeignAssetTrader<T: Config, R: TakeRevenue> {
+	weight: Weight,
+	paid: Option<(MultiLocation, u128)>,
+	_phantom: PhantomData<(T, R)>,
+}
+
+impl<T: Config, R: TakeRevenue> ForeignAssetTrader<T, R> {
+	/// The amount of an asset with the given rate that pays for `weight`.
+	fn amount(units_per_second: u128, weight: Weight) -> u128 {
+		units_per_second.saturating_mul(weight.ref_time() as u128) /
+			(WEIGHT_REF_TIME_PER_SECOND as u128)
+	}
+}
+
+impl<T: Config, R: TakeRevenue> WeightTrader for ForeignAssetTrader<T, R> {
+	fn new() -> Self {
+		Self { weight: Weight::zero(), paid: None, _phantom: PhantomData }
+	}
+
+	fn buy_weight(
+		&mut self,
+		weight: Weight,
+		payment: Assets,
+		_context: &XcmContext,
+	) -> Result<Assets, XcmError> {
+		log::trace!(target: LOG_TARGET, "buy_weight weight: {:?}, payment: {:?}", weight, payment);
+
+		let (location, units_per_second) = match self.paid {
+			Some((location, _)) =>
+				(location, Pallet::<T>::units_per_second(location).ok_or(XcmError::TooExpensive)?),
+			None => payment
+				.fungible_assets_iter()
+				.find_map(|asset| match asset.id {
+					Concrete(location) =>
+						Pallet::<T>::units_per_second(location).map(|rate| (location, rate)),
+					_ => None,
+				})
+				.ok_or(XcmError::TooExpensive)?,
+		};
+
+		let amount = Self::amount(units_per_second, weight);
+		let unused = payment
+			.checked_sub((location, amount).into())
+			.map_err(|_| XcmError::TooExpensive)?;
+
+		self.weight = self.weight.saturating_add(weight);
+		let paid = self.paid.map_or(0, |(_, paid)| paid);
+		self.paid = Some((location, paid.saturating_add(amount)));
+		Ok(unused)
+	}
+
+	fn refund_weight(&mut self, weight: Weight, _context: &XcmContext) -> Option<MultiAsset> {
+		log::trace!(target: LOG_TARGET, "refund_weight weight: {:?}", weight);
+
+		let (location, paid) = self.paid?;
+		let weight = weight.min(self.weight);
+		let units_per_second = Pallet::<T>::units_per_second(location)?;
+		let amount = Self::amount(units_per_second, weight).min(paid);
+
+		self.weight = self.weight.saturating_sub(weight);
+		self.paid = Some((location, paid.saturating_sub(amount)));
+		(amount > 0).then(|| (location, amount).into())
+	}
+}
+
+impl<T: Config, R: TakeRevenue> Drop for ForeignAssetTrader<T, R> {
+	fn drop(&mut self) {
+		if let Some((location, paid)) = self.paid {
+			if paid > 0 {
+				R::take_revenue((location, paid).into());
+			}
+		}
+	}
+}
@@ -0,0 +1,42 @@
+//! Weights for pallet_parachain_xcm_fees
+//!
+//! These are hand-estimated placeholders, not benchmark output: each is a rough execution time
+//! plus the storage accesses listed above it. Regenerate this file from the pallet's benchmarks
+//! on reference hardware before relying on it on a live chain:
+//!
+//! ```text
+//! ./target/release/parachain-template-node benchmark pallet --chain dev \
+//! 	--pallet pallet_parachain_xcm_fees --extrinsic '*' --steps 50 --repeat 20 \
+//! 	--output pallets/xcm-fees/src/weights.rs
+//! ```
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_parachain_xcm_fees.
+pub trait WeightInfo {
+	fn set_units_per_second() -> Weight;
+}
+
+/// Placeholder weights for pallet_parachain_xcm_fees, until it is benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: XcmFees UnitsPerSecond (r:0 w:1)
+	fn set_units_per_second() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: XcmFees UnitsPerSecond (r:0 w:1)
+	fn set_units_per_second() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,28 @@
+//! Runtime APIs declared by this runtime, in addition to those of the pallets it uses.
+
+use codec::Codec;
+use xcm::{VersionedMultiAssets, VersionedMultiLocation, VersionedXcm};
+
+sp_api::decl_runtime_apis! {
+	/// Quotes transaction fees in the assets of `Assets` that can pay them.
+	pub trait AssetTxPaymentApi<AssetId, Balance> where
+		AssetId: Codec,
+		Balance: Codec,
+	{
+		/// The fee `uxt` would pay, excluding any tip, if paid in `asset_id`.
+		///
+		/// Returns `None` if `asset_id` cannot be used to pay fees.
+		fn query_fee_in_asset(uxt: Block::Extrinsic, len: u32, asset_id: AssetId) -> Option<Balance>;
+	}
+
+	/// Quotes the fees for delivering XCM messages from this chain.
+	pub trait XcmDeliveryFeesApi {
+		/// The price of delivering `message` to `destination`, given the queues of this block.
+		///
+		/// Returns `None` if the message cannot be sent there.
+		fn query_delivery_fees(
+			destination: VersionedMultiLocation,
+			message: VersionedXcm<()>,
+		) -> Option<VersionedMultiAssets>;
+	}
+}
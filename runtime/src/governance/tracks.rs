@@ -0,0 +1,109 @@
+//! Track configurations for governance.
+
+use super::*;
+
+const fn percent(x: u32) -> sp_runtime::Perbill {
+	sp_runtime::Perbill::from_percent(x)
+}
+use pallet_referenda::Curve;
+const APP_ROOT: Curve =
+	Curve::LinearDecreasing { length: percent(100), floor: percent(50), ceil: percent(100) };
+const SUP_ROOT: Curve =
+	Curve::LinearDecreasing { length: percent(100), floor: percent(5), ceil: percent(50) };
+const APP_GENERAL_ADMIN: Curve =
+	Curve::LinearDecreasing { length: percent(100), floor: percent(50), ceil: percent(100) };
+const SUP_GENERAL_ADMIN: Curve =
+	Curve::LinearDecreasing { length: percent(50), floor: percent(1), ceil: percent(25) };
+const APP_REFERENDUM_CANCELLER: Curve =
+	Curve::LinearDecreasing { length: percent(50), floor: percent(50), ceil: percent(100) };
+const SUP_REFERENDUM_CANCELLER: Curve =
+	Curve::LinearDecreasing { length: percent(50), floor: percent(1), ceil: percent(10) };
+const APP_REFERENDUM_KILLER: Curve =
+	Curve::LinearDecreasing { length: percent(50), floor: percent(50), ceil: percent(100) };
+const SUP_REFERENDUM_KILLER: Curve =
+	Curve::LinearDecreasing { length: percent(50), floor: percent(1), ceil: percent(10) };
+
+const TRACKS_DATA: [(u16, pallet_referenda::TrackInfo<Balance, BlockNumber>); 4] = [
+	(
+		0,
+		pallet_referenda::TrackInfo {
+			name: "root",
+			max_deciding: 1,
+			decision_deposit: 1_000 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: DAYS,
+			min_enactment_period: DAYS,
+			min_approval: APP_ROOT,
+			min_support: SUP_ROOT,
+		},
+	),
+	(
+		1,
+		pallet_referenda::TrackInfo {
+			name: "general_admin",
+			max_deciding: 10,
+			decision_deposit: 100 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: 3 * HOURS,
+			min_enactment_period: 10 * MINUTES,
+			min_approval: APP_GENERAL_ADMIN,
+			min_support: SUP_GENERAL_ADMIN,
+		},
+	),
+	(
+		2,
+		pallet_referenda::TrackInfo {
+			name: "referendum_canceller",
+			max_deciding: 20,
+			decision_deposit: 100 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: 3 * HOURS,
+			min_enactment_period: 10 * MINUTES,
+			min_approval: APP_REFERENDUM_CANCELLER,
+			min_support: SUP_REFERENDUM_CANCELLER,
+		},
+	),
+	(
+		3,
+		pallet_referenda::TrackInfo {
+			name: "referendum_killer",
+			max_deciding: 20,
+			decision_deposit: 500 * UNIT,
+			prepare_period: 2 * HOURS,
+			decision_period: 7 * DAYS,
+			confirm_period: 3 * HOURS,
+			min_enactment_period: 10 * MINUTES,
+			min_approval: APP_REFERENDUM_KILLER,
+			min_support: SUP_REFERENDUM_KILLER,
+		},
+	),
+];
+
+pub struct TracksInfo;
+impl pallet_referenda::TracksInfo<Balance, BlockNumber> for TracksInfo {
+	type Id = u16;
+	type RuntimeOrigin = <RuntimeOrigin as frame_support::traits::OriginTrait>::PalletsOrigin;
+	fn tracks() -> &'static [(Self::Id, pallet_referenda::TrackInfo<Balance, BlockNumber>)] {
+		&TRACKS_DATA[..]
+	}
+	fn track_for(id: &Self::RuntimeOrigin) -> Result<Self::Id, ()> {
+		if let Ok(system_origin) = frame_system::RawOrigin::try_from(id.clone()) {
+			match system_origin {
+				frame_system::RawOrigin::Root => Ok(0),
+				_ => Err(()),
+			}
+		} else if let Ok(custom_origin) = origins::Origin::try_from(id.clone()) {
+			match custom_origin {
+				origins::Origin::GeneralAdmin => Ok(1),
+				origins::Origin::ReferendumCanceller => Ok(2),
+				origins::Origin::ReferendumKiller => Ok(3),
+			}
+		} else {
+			Err(())
+		}
+	}
+}
+pallet_referenda::impl_tracksinfo_get!(TracksInfo, Balance, BlockNumber);
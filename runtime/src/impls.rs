@@ -0,0 +1,72 @@
+//! Handlers for the imbalances created by fees, in the native token and in `Assets`.
+
+use super::{
+	AccountId, Assets, Authorship, Balances, FeesToTreasury, TipsToTreasury, Treasury,
+	TreasuryAccount,
+};
+use frame_support::traits::{fungibles, Currency, Get, Imbalance, OnUnbalanced};
+use pallet_asset_tx_payment::HandleCredit;
+use sp_runtime::Percent;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Gives `amount` to the block author, or to the treasury if there is no author.
+pub struct ToAuthorOrTreasury;
+impl OnUnbalanced<NegativeImbalance> for ToAuthorOrTreasury {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		match Authorship::author() {
+			Some(author) => Balances::resolve_creating(&author, amount),
+			None => Treasury::on_unbalanced(amount),
+		}
+	}
+}
+
+/// Splits fees between the treasury and the block author by [`FeesToTreasury`], and tips by
+/// [`TipsToTreasury`].
+///
+/// The author's part is rounded down, so whatever cannot be split evenly goes to the treasury.
+pub struct DealWithFees;
+impl DealWithFees {
+	fn split(amount: NegativeImbalance, to_treasury: Percent) {
+		let to_author = to_treasury.left_from_one().mul_floor(amount.peek());
+		let (to_author, to_treasury) = amount.split(to_author);
+		ToAuthorOrTreasury::on_unbalanced(to_author);
+		Treasury::on_unbalanced(to_treasury);
+	}
+}
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	/// The transaction payment pallet passes the fee first, then the tip.
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			Self::split(fees, FeesToTreasury::get());
+			if let Some(tips) = fees_then_tips.next() {
+				Self::split(tips, TipsToTreasury::get());
+			}
+		}
+	}
+
+	fn on_nonzero_unbalanced(fees: NegativeImbalance) {
+		Self::split(fees, FeesToTreasury::get());
+	}
+}
+
+/// Splits fees paid in `Assets` between the treasury account and the block author by
+/// [`FeesToTreasury`], like [`DealWithFees`] does for fees paid in the native token.
+///
+/// The asset transaction payment adapter hands over the fee and the tip as a single credit, so the
+/// tip is split like the fee.
+pub struct DealWithAssetFees;
+impl HandleCredit<AccountId, Assets> for DealWithAssetFees {
+	fn handle_credit(credit: fungibles::Credit<AccountId, Assets>) {
+		let to_author = FeesToTreasury::get().left_from_one().mul_floor(credit.peek());
+		let (to_author, to_treasury) = credit.split(to_author);
+		let author = Authorship::author().unwrap_or_else(TreasuryAccount::get);
+		// In case of error a credit is dropped, burning that part of the fee.
+		let _ = <Assets as fungibles::Balanced<AccountId>>::resolve(&author, to_author);
+		let _ = <Assets as fungibles::Balanced<AccountId>>::resolve(
+			&TreasuryAccount::get(),
+			to_treasury,
+		);
+	}
+}
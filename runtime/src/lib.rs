@@ -6,6 +6,10 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+pub mod apis;
+pub mod governance;
+pub mod impls;
+pub mod migrations;
 mod weights;
 pub mod xcm_config;
 
@@ -15,8 +19,11 @@ use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, Verify},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	traits::{
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto,
+		Extrinsic as ExtrinsicT, IdentifyAccount, SaturatedConversion, StaticLookup, Verify,
+	},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, MultiSignature,
 };
 
@@ -29,21 +36,29 @@ use frame_support::{
 	construct_runtime,
 	dispatch::DispatchClass,
 	parameter_types,
-	traits::{ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, Everything},
+	traits::{
+		AsEnsureOriginWithArg, ConstBool, ConstU32, ConstU64, ConstU8, Contains, EitherOfDiverse,
+		EnqueueWithOrigin, EqualPrivilegeOnly, NeverEnsureOrigin,
+	},
 	weights::{
-		constants::WEIGHT_REF_TIME_PER_SECOND, ConstantMultiplier, Weight, WeightToFeeCoefficient,
-		WeightToFeeCoefficients, WeightToFeePolynomial,
+		constants::{WEIGHT_REF_TIME_PER_MILLIS, WEIGHT_REF_TIME_PER_SECOND},
+		ConstantMultiplier, Weight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
 	},
 	PalletId,
 };
 use frame_system::{
 	limits::{BlockLength, BlockWeights},
-	EnsureRoot,
+	EnsureRoot, EnsureSigned,
 };
+use governance::{pallet_custom_origins, GeneralAdminOrigin};
+use pallet_message_queue::weights::WeightInfo as _;
 use pallet_xcm::{EnsureXcm, IsVoiceOfBody};
 pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-pub use sp_runtime::{MultiAddress, Perbill, Permill};
-use xcm_config::{RelayLocation, XcmConfig, XcmOriginToTransactDispatchOrigin};
+pub use sp_runtime::{MultiAddress, Perbill, Percent, Permill};
+use xcm_config::{
+	AggregateMessageOrigin, ForeignCreators, RelayLocation, XcmOriginToTransactDispatchOrigin,
+};
 
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -54,8 +69,7 @@ use polkadot_runtime_common::{BlockHashCount, SlowAdjustingFeeUpdate};
 use weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight};
 
 // XCM Imports
-use xcm::latest::prelude::BodyId;
-use xcm_executor::XcmExecutor;
+use xcm::latest::prelude::{BodyId, MultiLocation};
 
 /// Import the template pallet.
 pub use pallet_parachain_template;
@@ -70,6 +84,9 @@ pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::Account
 /// Balance of an account.
 pub type Balance = u128;
 
+/// Identifier of an asset in the local `Assets` instance.
+pub type AssetId = u32;
+
 /// Index of a transaction in the chain.
 pub type Nonce = u32;
 
@@ -103,7 +120,7 @@ pub type SignedExtra = (
 	frame_system::CheckEra<Runtime>,
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
-	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -113,6 +130,23 @@ pub type UncheckedExtrinsic =
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, RuntimeCall, SignedExtra>;
 
+/// The payload being signed in transactions.
+pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+
+/// Migrations to apply on runtime upgrade.
+///
+/// It is safe to leave an entry in place for a release after it has run: the template pallet's
+/// migration checks the pallet's on-chain storage version, and `RemoveSudo` is not gated by one
+/// but only clears a prefix that is already empty, at the cost of a read.
+///
+/// `SetXcmpMaxIndividualWeight` is the exception: it sets the weight again at every upgrade, over
+/// any set through governance since, so it should go once it has run.
+pub type Migrations = (
+	pallet_parachain_template::migrations::v1::MigrateToV1<Runtime>,
+	migrations::RemoveSudo,
+	migrations::SetXcmpMaxIndividualWeight,
+);
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -120,6 +154,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
@@ -261,6 +296,19 @@ parameter_types! {
 
 // Configure FRAME pallets to include in runtime.
 
+/// Lets every call through but `MessageQueue::execute_overweight`, which any signed account could
+/// otherwise use. Overweight messages are executed through `DmpQueue::execute_overweight` instead,
+/// by `GeneralAdminOrigin`.
+pub struct BaseFilter;
+impl Contains<RuntimeCall> for BaseFilter {
+	fn contains(call: &RuntimeCall) -> bool {
+		!matches!(
+			call,
+			RuntimeCall::MessageQueue(pallet_message_queue::Call::execute_overweight { .. })
+		)
+	}
+}
+
 impl frame_system::Config for Runtime {
 	/// The identifier used to distinguish between accounts.
 	type AccountId = AccountId;
@@ -295,7 +343,7 @@ impl frame_system::Config for Runtime {
 	/// The weight of database operations that the runtime can invoke.
 	type DbWeight = RocksDbWeight;
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = Everything;
+	type BaseCallFilter = BaseFilter;
 	/// Weight information for the extrinsics of this pallet.
 	type SystemWeightInfo = ();
 	/// Block & extrinsics weights: base values and limits.
@@ -332,7 +380,7 @@ impl pallet_balances::Config for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type RuntimeEvent = RuntimeEvent;
-	type DustRemoval = ();
+	type DustRemoval = Treasury;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
@@ -340,10 +388,72 @@ impl pallet_balances::Config for Runtime {
 	type ReserveIdentifier = [u8; 8];
 	type RuntimeHoldReason = RuntimeHoldReason;
 	type FreezeIdentifier = ();
-	type MaxHolds = ConstU32<0>;
+	type MaxHolds = ConstU32<1>;
 	type MaxFreezes = ConstU32<0>;
 }
 
+parameter_types! {
+	pub const AssetDeposit: Balance = 10 * UNIT;
+	pub const AssetAccountDeposit: Balance = MILLIUNIT;
+	pub const MetadataDepositBase: Balance = UNIT;
+	pub const MetadataDepositPerByte: Balance = 10 * MICROUNIT;
+	pub const ApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+	pub const AssetsStringLimit: u32 = 50;
+}
+
+/// Assets created on this chain, identified by a number.
+pub type LocalAssetsInstance = pallet_assets::Instance1;
+
+impl pallet_assets::Config<LocalAssetsInstance> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<1000>;
+	type AssetId = AssetId;
+	type AssetIdParameter = codec::Compact<AssetId>;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+	type ForceOrigin = GeneralAdminOrigin;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = ();
+}
+
+/// Assets of other consensus systems, identified by their location relative to this chain.
+pub type ForeignAssetsInstance = pallet_assets::Instance2;
+
+impl pallet_assets::Config<ForeignAssetsInstance> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Balance = Balance;
+	type RemoveItemsLimit = ConstU32<1000>;
+	type AssetId = MultiLocation;
+	type AssetIdParameter = MultiLocation;
+	type Currency = Balances;
+	// The relay chain and sibling parachains may register their own assets over XCM.
+	type CreateOrigin = ForeignCreators;
+	type ForceOrigin = GeneralAdminOrigin;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type CallbackHandle = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = xcm_config::ForeignAssetsBenchmarkHelper;
+}
+
 parameter_types! {
 	/// Relay Chain `TransactionByteFee` / 10
 	pub const TransactionByteFee: Balance = 10 * MICROUNIT;
@@ -351,17 +461,97 @@ parameter_types! {
 
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+	type OnChargeTransaction =
+		pallet_transaction_payment::CurrencyAdapter<Balances, impls::DealWithFees>;
 	type WeightToFee = WeightToFee;
 	type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 	type OperationalFeeMultiplier = ConstU8<5>;
 }
 
-impl pallet_sudo::Config for Runtime {
+/// Converts native fees into `Assets` balances at the ratio of the asset's minimum balance to the
+/// existential deposit. Governance sets the rate of an asset through `force_asset_status`; only
+/// sufficient assets can pay fees.
+pub type NativeToAssetsBalance =
+	pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto, LocalAssetsInstance>;
+
+impl pallet_asset_tx_payment::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction =
+		pallet_asset_tx_payment::FungiblesAdapter<NativeToAssetsBalance, impls::DealWithAssetFees>;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"py/trsry");
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+	/// The share of transaction and XCM execution fees paid to the treasury; the rest goes to the
+	/// block author.
+	pub const FeesToTreasury: Percent = Percent::from_percent(80);
+	/// The share of tips paid to the treasury; the rest goes to the block author.
+	pub const TipsToTreasury: Percent = Percent::from_percent(0);
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = UNIT;
+	pub const SpendPeriod: BlockNumber = 6 * DAYS;
+}
+
+impl pallet_treasury::Config for Runtime {
+	type PalletId = TreasuryPalletId;
+	type Currency = Balances;
+	type ApproveOrigin = GeneralAdminOrigin;
+	type RejectOrigin = GeneralAdminOrigin;
+	type RuntimeEvent = RuntimeEvent;
+	type OnSlash = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type ProposalBondMaximum = ();
+	type SpendPeriod = SpendPeriod;
+	// Unspent funds are kept for later spend periods.
+	type Burn = ();
+	type BurnDestination = ();
+	type SpendFunds = ();
+	type MaxApprovals = ConstU32<100>;
+	// Funds are only spent through approved proposals.
+	type SpendOrigin = NeverEnsureOrigin<Balance>;
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+}
+
+impl pallet_parachain_xcm_fees::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = GeneralAdminOrigin;
+	type WeightInfo = pallet_parachain_xcm_fees::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight =
+		Perbill::from_percent(80) * RuntimeBlockWeights::get().max_block;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeEvent = RuntimeEvent;
+	type PalletsOrigin = OriginCaller;
 	type RuntimeCall = RuntimeCall;
-	type WeightInfo = ();
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = ConstU32<50>;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type Preimages = Preimage;
+}
+
+parameter_types! {
+	pub const PreimageBaseDeposit: Balance = UNIT;
+	pub const PreimageByteDeposit: Balance = 10 * MICROUNIT;
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
 }
 
 parameter_types! {
@@ -371,7 +561,7 @@ parameter_types! {
 
 impl cumulus_pallet_parachain_system::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnSystemEvent = ();
+	type OnSystemEvent = xcm_config::SubscribeToSiblingVersions;
 	type SelfParaId = parachain_info::Pallet<Runtime>;
 	type OutboundXcmpMessageSource = XcmpQueue;
 	type DmpMessageHandler = DmpQueue;
@@ -387,20 +577,84 @@ impl cumulus_pallet_aura_ext::Config for Runtime {}
 
 impl cumulus_pallet_xcmp_queue::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = InboundXcm;
 	type ChannelInfo = ParachainSystem;
-	type VersionWrapper = ();
-	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
-	type ControllerOrigin = EnsureRoot<AccountId>;
+	type VersionWrapper = PolkadotXcm;
+	type ExecuteOverweightOrigin = GeneralAdminOrigin;
+	type ControllerOrigin = GeneralAdminOrigin;
 	type ControllerOriginConverter = XcmOriginToTransactDispatchOrigin;
 	type WeightInfo = ();
-	type PriceForSiblingDelivery = ();
+	type PriceForSiblingDelivery = xcm_config::ExponentialDeliveryPrice;
 }
 
-impl cumulus_pallet_dmp_queue::Config for Runtime {
+parameter_types! {
+	/// The weight `MessageQueue` may spend executing the messages of the relay chain in each block.
+	pub ParentServiceWeight: Weight =
+		Perbill::from_percent(10) * RuntimeBlockWeights::get().max_block;
+	/// The weight `MessageQueue` may spend executing the messages of siblings in each block.
+	pub SiblingServiceWeight: Weight =
+		Perbill::from_percent(25) * RuntimeBlockWeights::get().max_block;
+	/// The weight `MessageQueue` may spend executing messages in each block.
+	pub MessageQueueServiceWeight: Weight =
+		ParentServiceWeight::get().saturating_add(SiblingServiceWeight::get());
+	/// The origin of the downward messages `DmpQueue` moves to `MessageQueue`.
+	pub const DmpOrigin: AggregateMessageOrigin = AggregateMessageOrigin::Parent;
+	/// The size of the heap of a page of `MessageQueue`: room for the largest message the relay
+	/// chain lets through by default, an HRMP message of 100 KiB, with some to spare for the page's
+	/// own encoding.
+	pub const MessageQueueHeapSize: u32 = 103 * 1024;
+	/// The proof of a full page of `MessageQueue`: its heap, and under 128 bytes for its key, its
+	/// counters and the length of its heap.
+	pub MessageQueuePageProof: Weight =
+		Weight::from_parts(0, MessageQueueHeapSize::get() as u64 + 128);
+	/// The weight of queueing a message in `MessageQueue`: reading and writing the book and last
+	/// page of its queue, and knitting the queue into the ready ring as benchmarked. The last page
+	/// may be full.
+	pub MessageQueueEnqueueWeight: Weight =
+		<Runtime as pallet_message_queue::Config>::WeightInfo::ready_ring_knit()
+			.saturating_add(RocksDbWeight::get().reads_writes(2, 2))
+			.saturating_add(MessageQueuePageProof::get());
+	/// The weight of executing an overweight message of `MessageQueue`, on top of the execution
+	/// itself. The benchmarks weigh pages of 64 KiB, so the proof of one of ours is added.
+	pub MessageQueueOverweightOverhead: Weight = {
+		type WeightInfo = <Runtime as pallet_message_queue::Config>::WeightInfo;
+		WeightInfo::execute_overweight_page_removed()
+			.max(WeightInfo::execute_overweight_page_updated())
+			.saturating_add(MessageQueuePageProof::get())
+	};
+	/// The weight `XcmpQueue` lets a single message use before setting it aside as overweight:
+	/// 20 ms as by default, and room for the proof of queueing a message in `MessageQueue`, twice
+	/// over, rather than the 64 KiB allowed by default.
+	pub XcmpMaxIndividualWeight: Weight = Weight::from_parts(
+		20 * WEIGHT_REF_TIME_PER_MILLIS,
+		2 * MessageQueueEnqueueWeight::get().proof_size(),
+	);
+}
+
+impl pallet_message_queue::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_message_queue::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type MessageProcessor =
+		pallet_message_queue::mock_helpers::NoopMessageProcessor<AggregateMessageOrigin>;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type MessageProcessor = InboundXcm;
+	type Size = u32;
+	type QueueChangeHandler = InboundXcm;
+	type QueuePausedQuery = ();
+	type HeapSize = MessageQueueHeapSize;
+	type MaxStale = ConstU32<8>;
+	type ServiceWeight = MessageQueueServiceWeight;
+}
+
+impl pallet_parachain_dmp_queue::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
-	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
+	type DmpSink = EnqueueWithOrigin<MessageQueue, DmpOrigin>;
+	type MessageQueue = MessageQueue;
+	type OverweightAddress = (AggregateMessageOrigin, u32, u32);
+	type ExecuteOverweightOrigin = GeneralAdminOrigin;
+	type OverweightOverhead = MessageQueueOverweightOverhead;
+	type WeightInfo = pallet_parachain_dmp_queue::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -436,9 +690,9 @@ parameter_types! {
 	pub const StakingAdminBodyId: BodyId = BodyId::Defense;
 }
 
-/// We allow root and the StakingAdmin to execute privileged collator selection operations.
+/// We allow governance and the StakingAdmin to execute privileged collator selection operations.
 pub type CollatorSelectionUpdateOrigin = EitherOfDiverse<
-	EnsureRoot<AccountId>,
+	GeneralAdminOrigin,
 	EnsureXcm<IsVoiceOfBody<RelayLocation, StakingAdminBodyId>>,
 >;
 
@@ -458,9 +712,92 @@ impl pallet_collator_selection::Config for Runtime {
 	type WeightInfo = ();
 }
 
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as ExtrinsicT>::SignaturePayload)> {
+		use codec::Encode;
+
+		let tip = 0;
+		// take the biggest period possible.
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number()
+			.saturated_into::<u64>()
+			// The `System::block_number` is initialized with `n+1`,
+			// so the actual block number is `n`.
+			.saturating_sub(1);
+		let era = generic::Era::mortal(period, current_block);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(era),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None),
+		);
+		let raw_payload = SignedPayload::new(call, extra)
+			.map_err(|e| {
+				log::warn!("Unable to create signed payload: {:?}", e);
+			})
+			.ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let address = <Runtime as frame_system::Config>::Lookup::unlookup(account);
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (address, signature, extra)))
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+parameter_types! {
+	pub const SomethingDeposit: Balance = 10 * MILLIUNIT;
+	pub const SubmissionInterval: BlockNumber = 10;
+	pub const TemplateUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+}
+
+/// The collators of the current session, whose offchain workers report values to the template
+/// pallet.
+pub struct SessionValidators;
+impl Contains<AccountId> for SessionValidators {
+	fn contains(who: &AccountId) -> bool {
+		Session::validators().contains(who)
+	}
+}
+
 /// Configure the pallet template in pallets/template.
 impl pallet_parachain_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type Currency = Balances;
+	type SomethingDeposit = SomethingDeposit;
+	type AuthorityId = pallet_parachain_template::crypto::TemplateAuthId;
+	type AuthorizedReporters = SessionValidators;
+	type SubmissionInterval = SubmissionInterval;
+	type SubmitUnsigned = ConstBool<true>;
+	type UnsignedPriority = TemplateUnsignedPriority;
+	type AdminOrigin = GeneralAdminOrigin;
+	type MaxHistory = ConstU32<100>;
+	type WeightInfo = pallet_parachain_template::weights::SubstrateWeight<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -476,9 +813,20 @@ construct_runtime!(
 		// Monetary stuff.
 		Balances: pallet_balances = 10,
 		TransactionPayment: pallet_transaction_payment = 11,
+		Assets: pallet_assets::<Instance1> = 12,
+		ForeignAssets: pallet_assets::<Instance2> = 13,
+		AssetTxPayment: pallet_asset_tx_payment = 14,
 
 		// Governance
-		Sudo: pallet_sudo = 15,
+		// Index 15 was used by `pallet_sudo`, see `migrations::RemoveSudo`.
+		Scheduler: pallet_scheduler = 16,
+		Preimage: pallet_preimage = 17,
+		Council: pallet_collective::<Instance1> = 18,
+		CouncilMembership: pallet_membership::<Instance1> = 19,
+		ConvictionVoting: pallet_conviction_voting = 40,
+		Referenda: pallet_referenda = 41,
+		Origins: pallet_custom_origins::{Origin} = 42,
+		Treasury: pallet_treasury = 43,
 
 		// Collator support. The order of these 4 are important and shall not change.
 		Authorship: pallet_authorship = 20,
@@ -491,7 +839,10 @@ construct_runtime!(
 		XcmpQueue: cumulus_pallet_xcmp_queue = 30,
 		PolkadotXcm: pallet_xcm = 31,
 		CumulusXcm: cumulus_pallet_xcm = 32,
-		DmpQueue: cumulus_pallet_dmp_queue = 33,
+		DmpQueue: pallet_parachain_dmp_queue = 33,
+		XcmFees: pallet_parachain_xcm_fees = 34,
+		MessageQueue: pallet_message_queue = 35,
+		InboundXcm: pallet_parachain_inbound_xcm = 36,
 
 		// Template
 		TemplatePallet: pallet_parachain_template = 50,
@@ -503,11 +854,23 @@ mod benches {
 	frame_benchmarking::define_benchmarks!(
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_assets, Assets]
+		[pallet_assets, ForeignAssets]
 		[pallet_session, SessionBench::<Runtime>]
 		[pallet_timestamp, Timestamp]
-		[pallet_sudo, Sudo]
+		[pallet_scheduler, Scheduler]
+		[pallet_preimage, Preimage]
+		[pallet_collective, Council]
+		[pallet_membership, CouncilMembership]
+		[pallet_conviction_voting, ConvictionVoting]
+		[pallet_referenda, Referenda]
+		[pallet_treasury, Treasury]
 		[pallet_collator_selection, CollatorSelection]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[pallet_message_queue, MessageQueue]
+		[pallet_parachain_dmp_queue, DmpQueue]
+		[pallet_parachain_xcm_fees, XcmFees]
+		[pallet_parachain_template, TemplatePallet]
 	);
 }
 
@@ -649,6 +1012,44 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl apis::AssetTxPaymentApi<Block, AssetId, Balance> for Runtime {
+		fn query_fee_in_asset(
+			uxt: <Block as BlockT>::Extrinsic,
+			len: u32,
+			asset_id: AssetId,
+		) -> Option<Balance> {
+			use frame_support::traits::tokens::ConversionToAssetBalance;
+
+			let fee = TransactionPayment::query_info(uxt, len).partial_fee;
+			NativeToAssetsBalance::to_asset_balance(fee, asset_id).ok()
+		}
+	}
+
+	impl apis::XcmDeliveryFeesApi<Block> for Runtime {
+		fn query_delivery_fees(
+			destination: xcm::VersionedMultiLocation,
+			message: xcm::VersionedXcm<()>,
+		) -> Option<xcm::VersionedMultiAssets> {
+			let destination = destination.try_into().ok()?;
+			let message = message.try_into().ok()?;
+			let (_, price) =
+				xcm::latest::validate_send::<xcm_config::XcmRouter>(destination, message).ok()?;
+			Some(price.into())
+		}
+	}
+
+	impl pallet_parachain_template_runtime_api::TemplateApi<Block, AccountId, Balance> for Runtime {
+		fn something() -> Option<u32> {
+			TemplatePallet::something().map(|record| record.value)
+		}
+
+		fn account_value(
+			who: AccountId,
+		) -> Option<pallet_parachain_template::AccountValue<Balance>> {
+			TemplatePallet::account_value(who)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
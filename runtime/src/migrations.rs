@@ -0,0 +1,106 @@
+//! Migrations of the runtime's own storage, as opposed to those shipped with its pallets.
+
+use super::*;
+use frame_support::{
+	migrations::RemovePallet,
+	storage_alias,
+	traits::{OnGenesis, OnRuntimeUpgrade},
+	weights::Weight,
+};
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
+
+parameter_types! {
+	pub const SudoPalletName: &'static str = "Sudo";
+	/// The index `pallet_sudo` had in `construct_runtime!`.
+	pub const SudoPalletIndex: u8 = 15;
+}
+
+/// The `Sudo::Key` storage item of the removed `pallet_sudo`.
+#[storage_alias]
+type SudoKey = StorageValue<Sudo, AccountId>;
+
+/// Retire `pallet_sudo` once it has been removed from `construct_runtime!`.
+///
+/// Clears everything left under the pallet's prefix, the sudo key included, and then checks, under
+/// try-runtime, that no privileged origin was left dangling.
+pub type RemoveSudo = (RemovePallet<SudoPalletName, RocksDbWeight>, EnsureNoDanglingPrivilege);
+
+/// Checks that the privileged origins are still reachable once sudo is gone, root and general
+/// admin through referenda, and that no pallet took over the index or name of `pallet_sudo`.
+///
+/// The council is only a second way to general admin. Nothing seeds it on an existing chain, so an
+/// empty council is reported but not treated as a failure.
+///
+/// Does nothing outside try-runtime.
+pub struct EnsureNoDanglingPrivilege;
+
+impl OnRuntimeUpgrade for EnsureNoDanglingPrivilege {
+	fn on_runtime_upgrade() -> Weight {
+		Weight::zero()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		use frame_support::{ensure, traits::PalletsInfoAccess};
+		use pallet_referenda::TracksInfo;
+
+		ensure!(!SudoKey::exists(), "the sudo key is still set");
+		ensure!(
+			AllPalletsWithSystem::infos()
+				.iter()
+				.all(|info| info.index != SudoPalletIndex::get() &&
+					info.name != SudoPalletName::get()),
+			"a pallet still uses the index or name of `pallet_sudo`"
+		);
+
+		let root: OriginCaller = frame_system::RawOrigin::Root.into();
+		ensure!(
+			governance::TracksInfo::track_for(&root).is_ok(),
+			"root is not reachable through referenda"
+		);
+		let general_admin: OriginCaller = pallet_custom_origins::Origin::GeneralAdmin.into();
+		ensure!(
+			governance::TracksInfo::track_for(&general_admin).is_ok(),
+			"general admin is not reachable through referenda"
+		);
+		if pallet_collective::Members::<Runtime, governance::CouncilCollective>::get().is_empty() {
+			log::warn!(
+				"The council has no members: general admin is only reachable through referenda."
+			);
+		}
+
+		Ok(())
+	}
+}
+
+/// Raises the weight `XcmpQueue` lets a single message use to `XcmpMaxIndividualWeight`, so that
+/// it keeps the messages of siblings it has no weight left for in a block rather than setting them
+/// aside as overweight, now that queueing them in `MessageQueue` weighs the proof of a full page.
+///
+/// Also run at genesis, through `InboundXcm`.
+pub struct SetXcmpMaxIndividualWeight;
+
+impl SetXcmpMaxIndividualWeight {
+	fn set() {
+		if let Err(e) = XcmpQueue::update_xcmp_max_individual_weight(
+			RuntimeOrigin::root(),
+			XcmpMaxIndividualWeight::get(),
+		) {
+			log::error!("Failed to set the maximum weight of a single XCMP message: {e:?}");
+		}
+	}
+}
+
+impl OnGenesis for SetXcmpMaxIndividualWeight {
+	fn on_genesis() {
+		Self::set();
+	}
+}
+
+impl OnRuntimeUpgrade for SetXcmpMaxIndividualWeight {
+	fn on_runtime_upgrade() -> Weight {
+		Self::set();
+		RocksDbWeight::get().reads_writes(1, 1)
+	}
+}
@@ -1,32 +1,74 @@
 use super::{
-	AccountId, AllPalletsWithSystem, Balances, ParachainInfo, ParachainSystem, PolkadotXcm,
-	Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, WeightToFee, XcmpQueue,
+	governance::GeneralAdminOrigin, impls::DealWithFees, migrations::SetXcmpMaxIndividualWeight,
+	AccountId, AllPalletsWithSystem, AssetId, Assets, Balance, Balances, ForeignAssets,
+	MessageQueue, MessageQueueEnqueueWeight, ParachainInfo, ParachainSystem, ParentServiceWeight,
+	PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, SiblingServiceWeight, System,
+	TransactionByteFee, TreasuryAccount, WeightToFee, XcmpQueue, MILLIUNIT,
 };
+use codec::{Decode, Encode};
+use cumulus_pallet_parachain_system::OnSystemEvent;
+use cumulus_primitives_core::PersistedValidationData;
+use cumulus_primitives_utility::PriceForParentDelivery;
 use frame_support::{
+	dispatch::DispatchClass,
 	match_types, parameter_types,
-	traits::{ConstU32, Everything, Nothing},
-	weights::Weight,
+	storage::types::ValueQuery,
+	storage_alias,
+	traits::{
+		fungible, fungibles, tokens::Preservation, ConstU32, ConstU64, Contains, ContainsPair,
+		EnsureOrigin, EnsureOriginWithArg, Equals, Everything, Get, Nothing, PalletInfoAccess,
+	},
+	weights::{constants::RocksDbWeight, Weight},
 };
-use frame_system::EnsureRoot;
-use pallet_xcm::XcmPassthrough;
-use polkadot_parachain::primitives::Sibling;
-use polkadot_runtime_common::impls::ToAuthor;
+use pallet_parachain_xcm_fees::ForeignAssetTrader;
+use pallet_xcm::{EnsureXcm, XcmPassthrough};
+use polkadot_parachain::primitives::{Id as ParaId, Sibling};
+use polkadot_runtime_common::xcm_sender::PriceForParachainDelivery;
+use sp_runtime::{
+	traits::{Identity, One, Saturating},
+	FixedPointNumber, FixedU128, Percent,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
 use xcm::latest::prelude::*;
 use xcm_builder::{
-	AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowTopLevelPaidExecutionFrom,
-	CurrencyAdapter, DenyReserveTransferToRelayChain, DenyThenTry, EnsureXcmOrigin,
-	FixedWeightBounds, IsConcrete, NativeAsset, ParentIsPreset, RelayChainAsNative,
+	AccountId32Aliases, AllowExplicitUnpaidExecutionFrom, AllowKnownQueryResponses,
+	AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom, AsPrefixedGeneralIndex,
+	ConvertedConcreteId, CurrencyAdapter, DenyReserveTransferToRelayChain, DenyThenTry,
+	EnsureXcmOrigin, FixedWeightBounds, FungiblesAdapter, IsConcrete, MatchedConvertedConcreteId,
+	MintLocation, NativeAsset, NoChecking, ParentIsPreset, ProcessXcmMessage, RelayChainAsNative,
 	SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountId32AsNative,
 	SignedToAccountId32, SovereignSignedViaLocation, TakeWeightCredit, TrailingSetTopicAsId,
 	UsingComponents, WithComputedOrigin, WithUniqueTopic,
 };
-use xcm_executor::XcmExecutor;
+use xcm_executor::{
+	traits::{ConvertLocation, FeeManager, FeeReason, JustTry, TakeRevenue, TransactAsset},
+	XcmExecutor,
+};
 
 parameter_types! {
 	pub const RelayLocation: MultiLocation = MultiLocation::parent();
 	pub const RelayNetwork: Option<NetworkId> = None;
 	pub RelayChainOrigin: RuntimeOrigin = cumulus_pallet_xcm::Origin::Relay.into();
 	pub UniversalLocation: InteriorMultiLocation = Parachain(ParachainInfo::parachain_id().into()).into();
+	pub AssetsPalletLocation: MultiLocation =
+		PalletInstance(<Assets as PalletInfoAccess>::index() as u8).into();
+	/// The location of the Asset Hub system parachain, seen from this chain.
+	pub const AssetHubLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(1000)));
+	/// Whether the relay chain token may be teleported to and from the relay chain and Asset Hub.
+	///
+	/// Both chains must trust this chain as a teleporter of the relay chain token too.
+	pub const TeleportRelayToken: bool = false;
+	/// The account tracking the relay chain token teleported to and from this chain.
+	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
+}
+
+/// The checking account of the relay chain token, if it can be teleported.
+pub struct RelayTokenCheckingAccount;
+impl Get<Option<(AccountId, MintLocation)>> for RelayTokenCheckingAccount {
+	fn get() -> Option<(AccountId, MintLocation)> {
+		// The relay chain token is minted on the relay chain, not here.
+		TeleportRelayToken::get().then(|| (CheckingAccount::get(), MintLocation::NonLocal))
+	}
 }
 
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
@@ -51,10 +93,158 @@ pub type LocalAssetTransactor = CurrencyAdapter<
 	LocationToAccountId,
 	// Our chain's account ID type (we can't get away without mentioning it explicitly):
 	AccountId,
-	// We don't track any teleports.
+	// Track the relay chain token teleported to and from this chain, if it can be teleported.
+	RelayTokenCheckingAccount,
+>;
+
+/// Converts the location of a local asset, `PalletInstance(Assets)/GeneralIndex(id)`, into its
+/// `AssetId` in `Assets`.
+pub type LocalAssetsConvertedConcreteId = ConvertedConcreteId<
+	AssetId,
+	Balance,
+	AsPrefixedGeneralIndex<AssetsPalletLocation, AssetId, JustTry>,
+	JustTry,
+>;
+
+/// Means for transacting assets created on this chain.
+pub type LocalFungiblesTransactor = FungiblesAdapter<
+	// Use this fungibles implementation:
+	Assets,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	LocalAssetsConvertedConcreteId,
+	// Convert an XCM MultiLocation into a local account id:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We don't track any teleports of `Assets`.
+	NoChecking,
+	// We don't track any teleports of `Assets`, but a placeholder account is provided due to trait
+	// bounds.
 	(),
 >;
 
+/// The locations of the assets transacted by `LocalAssetTransactor`, which `ForeignAssets` must
+/// never take over.
+pub type LocalAssetLocations = Equals<RelayLocation>;
+
+/// Matches the locations of assets registered in `ForeignAssets`, other than those transacted by
+/// `LocalAssetTransactor`.
+pub struct IsRegisteredForeignAsset;
+impl Contains<MultiLocation> for IsRegisteredForeignAsset {
+	fn contains(location: &MultiLocation) -> bool {
+		!LocalAssetLocations::contains(location) &&
+			<ForeignAssets as fungibles::Inspect<AccountId>>::asset_exists(*location)
+	}
+}
+
+/// Converts the location of a registered foreign asset into its `AssetId` in `ForeignAssets`,
+/// which is the location itself.
+pub type ForeignAssetsConvertedConcreteId =
+	MatchedConvertedConcreteId<MultiLocation, Balance, IsRegisteredForeignAsset, Identity, JustTry>;
+
+/// Means for transacting assets of other consensus systems, such as sibling parachain tokens.
+pub type ForeignFungiblesTransactor = FungiblesAdapter<
+	// Use this fungibles implementation:
+	ForeignAssets,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	ForeignAssetsConvertedConcreteId,
+	// Convert an XCM MultiLocation into a local account id:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We don't track any teleports of `ForeignAssets`.
+	NoChecking,
+	// We don't track any teleports of `ForeignAssets`, but a placeholder account is provided due
+	// to trait bounds.
+	(),
+>;
+
+/// Means for transacting assets on this chain.
+///
+/// Each asset is matched by at most one of them: `ForeignFungiblesTransactor` skips the relay
+/// chain token, which is always transacted by `LocalAssetTransactor`.
+pub type AssetTransactors =
+	(ForeignFungiblesTransactor, LocalAssetTransactor, LocalFungiblesTransactor);
+
+/// Allows the relay chain and sibling parachains to create assets in `ForeignAssets` located
+/// under their own location, owned by their sovereign account.
+///
+/// The relay chain may not create its own token, which is transacted by `LocalAssetTransactor`,
+/// nor assets located under a sibling parachain.
+pub struct ForeignCreators;
+impl EnsureOriginWithArg<RuntimeOrigin, MultiLocation> for ForeignCreators {
+	type Success = AccountId;
+
+	fn try_origin(
+		origin: RuntimeOrigin,
+		asset_location: &MultiLocation,
+	) -> Result<Self::Success, RuntimeOrigin> {
+		let origin_location = EnsureXcm::<Everything>::try_origin(origin.clone())?;
+		if origin_location.parents != 1 || !asset_location.starts_with(&origin_location) {
+			return Err(origin)
+		}
+		let under_sibling = matches!(asset_location.first_interior(), Some(Parachain(_)));
+		if LocalAssetLocations::contains(asset_location) ||
+			(origin_location == RelayLocation::get() && under_sibling)
+		{
+			return Err(origin)
+		}
+		LocationToAccountId::convert_location(&origin_location).ok_or(origin)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin(asset_location: &MultiLocation) -> Result<RuntimeOrigin, ()> {
+		Ok(pallet_xcm::Origin::Xcm(*asset_location).into())
+	}
+}
+
+/// Creates `ForeignAssets` ids under a sibling parachain for benchmarking.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct ForeignAssetsBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_assets::BenchmarkHelper<MultiLocation> for ForeignAssetsBenchmarkHelper {
+	fn create_asset_id_parameter(id: u32) -> MultiLocation {
+		MultiLocation::new(1, X1(Parachain(id)))
+	}
+}
+
+/// The reserves trusted for assets that are reserve transferred to this chain: the relay chain for
+/// its token, and Asset Hub for the assets it holds, such as USDT.
+pub struct TrustedReserves;
+impl ContainsPair<MultiAsset, MultiLocation> for TrustedReserves {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		let Concrete(location) = asset.id else { return false };
+		if *origin == RelayLocation::get() {
+			location == RelayLocation::get()
+		} else if *origin == AssetHubLocation::get() {
+			location.starts_with(origin)
+		} else {
+			false
+		}
+	}
+}
+
+/// The chains trusted to teleport the relay chain token to this chain, if `TeleportRelayToken`
+/// is set: the relay chain and Asset Hub.
+pub struct TrustedTeleporters;
+impl ContainsPair<MultiAsset, MultiLocation> for TrustedTeleporters {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		TeleportRelayToken::get() &&
+			asset.id == Concrete(RelayLocation::get()) &&
+			(*origin == RelayLocation::get() || *origin == AssetHubLocation::get())
+	}
+}
+
+/// Only lets local origins teleport the relay chain token, and only if `TeleportRelayToken` is
+/// set, so that nothing is burnt here only to be rejected by the destination.
+pub struct OnlyRelayTokenTeleports;
+impl Contains<(MultiLocation, Vec<MultiAsset>)> for OnlyRelayTokenTeleports {
+	fn contains((_origin, assets): &(MultiLocation, Vec<MultiAsset>)) -> bool {
+		TeleportRelayToken::get() &&
+			assets.iter().all(|asset| asset.id == Concrete(RelayLocation::get()))
+	}
+}
+
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
 /// biases the kind of local `Origin` it will become.
@@ -88,6 +278,10 @@ match_types! {
 		MultiLocation { parents: 1, interior: Here } |
 		MultiLocation { parents: 1, interior: X1(Plurality { id: BodyId::Executive, .. }) }
 	};
+	pub type ParentOrSiblings: impl Contains<MultiLocation> = {
+		MultiLocation { parents: 1, interior: Here } |
+		MultiLocation { parents: 1, interior: X1(_) }
+	};
 }
 
 pub type Barrier = TrailingSetTopicAsId<
@@ -95,11 +289,15 @@ pub type Barrier = TrailingSetTopicAsId<
 		DenyReserveTransferToRelayChain,
 		(
 			TakeWeightCredit,
+			// Responses to our queries, such as version notifications, are free.
+			AllowKnownQueryResponses<PolkadotXcm>,
 			WithComputedOrigin<
 				(
 					AllowTopLevelPaidExecutionFrom<Everything>,
 					AllowExplicitUnpaidExecutionFrom<ParentOrParentsExecutivePlurality>,
 					// ^^^ Parent and its exec plurality get free execution
+					// The relay chain and siblings may subscribe to our XCM version for free.
+					AllowSubscriptionsFrom<ParentOrSiblings>,
 				),
 				UniversalLocation,
 				ConstU32<8>,
@@ -108,20 +306,58 @@ pub type Barrier = TrailingSetTopicAsId<
 	>,
 >;
 
+/// Deposits the fees paid in foreign assets into the treasury account.
+pub struct ToTreasury;
+impl TakeRevenue for ToTreasury {
+	fn take_revenue(revenue: MultiAsset) {
+		let treasury = AccountId32 { network: None, id: TreasuryAccount::get().into() };
+		// In case of error the revenue is dropped, burning the fee.
+		let _ = AssetTransactors::deposit_asset(
+			&revenue,
+			&treasury.into(),
+			&XcmContext::with_message_id([0; 32]),
+		);
+	}
+}
+
+/// Waives the delivery fees of messages sent while executing XCM as the chain itself, and gives
+/// the fees paid by every other origin to the treasury.
+///
+/// Local accounts pay for the messages their transfers send: `pallet_xcm` sets the fees mode of
+/// `reserve_transfer_assets` and `teleport_assets` to withdraw them from the account, rather than
+/// from holding. Messages that they send with `pallet_xcm::send` are charged by [`ChargeSender`].
+pub struct DeliveryFeeManager;
+impl FeeManager for DeliveryFeeManager {
+	fn is_waived(origin: Option<&MultiLocation>, _: FeeReason) -> bool {
+		origin == Some(&MultiLocation::here())
+	}
+
+	fn handle_fee(fee: MultiAssets) {
+		fee.into_inner().into_iter().for_each(ToTreasury::take_revenue);
+	}
+}
+
+/// Pays for execution in the relay chain token, split between the treasury and the block author,
+/// or else in any foreign asset that governance set a rate for in `XcmFees`.
+pub type Trader = (
+	UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, DealWithFees>,
+	ForeignAssetTrader<Runtime, ToTreasury>,
+);
+
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
 	type RuntimeCall = RuntimeCall;
 	type XcmSender = XcmRouter;
 	// How to withdraw and deposit an asset.
-	type AssetTransactor = LocalAssetTransactor;
+	type AssetTransactor = AssetTransactors;
 	type OriginConverter = XcmOriginToTransactDispatchOrigin;
-	type IsReserve = NativeAsset;
-	type IsTeleporter = (); // Teleporting is disabled.
+	// Siblings remain the reserve of their own native token.
+	type IsReserve = (NativeAsset, TrustedReserves);
+	type IsTeleporter = TrustedTeleporters;
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
-	type Trader =
-		UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ToAuthor<Runtime>>;
+	type Trader = Trader;
 	type ResponseHandler = PolkadotXcm;
 	type AssetTrap = PolkadotXcm;
 	type AssetClaims = PolkadotXcm;
@@ -130,7 +366,7 @@ impl xcm_executor::Config for XcmConfig {
 	type MaxAssetsIntoHolding = MaxAssetsIntoHolding;
 	type AssetLocker = ();
 	type AssetExchanger = ();
-	type FeeManager = ();
+	type FeeManager = DeliveryFeeManager;
 	type MessageExporter = ();
 	type UniversalAliases = Nothing;
 	type CallDispatcher = RuntimeCall;
@@ -141,15 +377,185 @@ impl xcm_executor::Config for XcmConfig {
 /// No local origins on this chain are allowed to dispatch XCM sends/executions.
 pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
 
+parameter_types! {
+	/// The price of delivering a message to an empty queue, before its size is accounted for.
+	pub const BaseDeliveryFee: Balance = 3 * MILLIUNIT;
+	/// How much the price of a delivery grows with every message already queued for the
+	/// destination.
+	pub const DeliveryFeeGrowth: Percent = Percent::from_percent(5);
+}
+
+/// The state of an outbound channel of `XcmpQueue`, which keeps the fields of its own
+/// `OutboundChannelDetails` private.
+///
+/// A storage item that no longer decodes reads as empty, which would price every delivery at the
+/// base price. The layout is pinned against the upstream type by the tests below, and the storage
+/// key and the meaning of the indices by the delivery fee tests of the XCM simulator.
+#[derive(Encode, Decode)]
+struct OutboundChannelDetails {
+	recipient: ParaId,
+	_state: cumulus_pallet_xcmp_queue::OutboundState,
+	_signals_exist: bool,
+	/// The pages from `first_index` up to `last_index` are waiting to be sent.
+	first_index: u16,
+	last_index: u16,
+}
+
+/// The outbound channels of `XcmpQueue`.
+#[storage_alias]
+type OutboundXcmpStatus = StorageValue<XcmpQueue, Vec<OutboundChannelDetails>, ValueQuery>;
+
+/// The upward messages `ParachainSystem` holds until the relay chain has room for them.
+#[storage_alias]
+type PendingUpwardMessages = StorageValue<ParachainSystem, Vec<Vec<u8>>, ValueQuery>;
+
+/// Prices the delivery of a message at `BaseDeliveryFee` plus `TransactionByteFee` per byte, in the
+/// relay chain token, compounded by `DeliveryFeeGrowth` for every message queued for the
+/// destination, so that filling a queue gets exponentially more expensive.
+///
+/// The queues are those the relay chain reported in the validation data of this block, plus what
+/// this chain holds back until there is room in them: the pages `XcmpQueue` keeps for a sibling,
+/// and the upward messages `ParachainSystem` keeps for the relay chain.
+pub struct ExponentialDeliveryPrice;
+impl ExponentialDeliveryPrice {
+	fn price(queued: u32, message: &Xcm<()>) -> MultiAssets {
+		let size = message.encoded_size() as Balance;
+		let fee =
+			BaseDeliveryFee::get().saturating_add(TransactionByteFee::get().saturating_mul(size));
+		let factor = FixedU128::one()
+			.saturating_add(DeliveryFeeGrowth::get().into())
+			.saturating_pow(queued as usize);
+		(RelayLocation::get(), factor.saturating_mul_int(fee)).into()
+	}
+}
+
+impl PriceForParachainDelivery for ExponentialDeliveryPrice {
+	fn price_for_parachain_delivery(para: ParaId, message: &Xcm<()>) -> MultiAssets {
+		let queued = ParachainSystem::relevant_messaging_state()
+			.and_then(|state| state.egress_channels.into_iter().find(|(id, _)| *id == para))
+			.map_or(0, |(_, channel)| channel.msg_count);
+		let held = OutboundXcmpStatus::get()
+			.into_iter()
+			.find(|channel| channel.recipient == para)
+			.map_or(0, |channel| channel.last_index.saturating_sub(channel.first_index));
+		Self::price(queued.saturating_add(held.into()), message)
+	}
+}
+
+impl PriceForParentDelivery for ExponentialDeliveryPrice {
+	fn price_for_parent_delivery(message: &Xcm<()>) -> MultiAssets {
+		let queued = match (
+			ParachainSystem::host_configuration(),
+			ParachainSystem::relevant_messaging_state(),
+		) {
+			(Some(config), Some(state)) => config
+				.max_upward_queue_count
+				.saturating_sub(state.relay_dispatch_queue_remaining_capacity.remaining_count),
+			_ => 0,
+		};
+		let held = PendingUpwardMessages::decode_len().unwrap_or(0) as u32;
+		Self::price(queued.saturating_add(held), message)
+	}
+}
+
+/// Charges the account sending a message with `pallet_xcm::send` the price `Router` asks for its
+/// delivery, paying it to the treasury.
+///
+/// `pallet_xcm` prefixes the messages it sends for an account with a `DescendOrigin` into it.
+/// Messages without one, such as version notifications, are sent by the chain itself for free.
+pub struct ChargeSender<Router>(PhantomData<Router>);
+impl<Router: SendXcm> SendXcm for ChargeSender<Router> {
+	type Ticket = (Option<(AccountId, Balance)>, Router::Ticket);
+
+	fn validate(
+		destination: &mut Option<MultiLocation>,
+		message: &mut Option<Xcm<()>>,
+	) -> SendResult<Self::Ticket> {
+		let sender = match message.as_ref().and_then(|message| message.0.first()) {
+			Some(DescendOrigin(X1(AccountId32 { id, .. }))) => Some(AccountId::from(*id)),
+			_ => None,
+		};
+		let (ticket, price) = Router::validate(destination, message)?;
+		let charge = match sender {
+			Some(sender) => Some((sender, relay_token_amount(&price).ok_or(SendError::Fees)?)),
+			None => None,
+		};
+		Ok(((charge, ticket), price))
+	}
+
+	fn deliver((charge, ticket): Self::Ticket) -> Result<XcmHash, SendError> {
+		if let Some((sender, fee)) = charge {
+			<Balances as fungible::Mutate<_>>::transfer(
+				&sender,
+				&TreasuryAccount::get(),
+				fee,
+				Preservation::Preserve,
+			)
+			.map_err(|_| SendError::Fees)?;
+		}
+		Router::deliver(ticket)
+	}
+}
+
+/// The total of `assets`, if they are all the relay chain token.
+fn relay_token_amount(assets: &MultiAssets) -> Option<Balance> {
+	assets.inner().iter().try_fold(0, |total: Balance, asset| match asset {
+		MultiAsset { id: Concrete(location), fun: Fungible(amount) }
+			if *location == RelayLocation::get() =>
+			Some(total.saturating_add(*amount)),
+		_ => None,
+	})
+}
+
 /// The means for routing XCM messages which are not for local execution into the right message
 /// queues.
+///
+/// Both queues wrap messages in the XCM version `PolkadotXcm` knows the destination supports, and
+/// price their delivery with [`ExponentialDeliveryPrice`].
 pub type XcmRouter = WithUniqueTopic<(
 	// Two routers - use UMP to communicate with the relay chain:
-	cumulus_primitives_utility::ParentAsUmp<ParachainSystem, (), ()>,
+	cumulus_primitives_utility::ParentAsUmp<ParachainSystem, PolkadotXcm, ExponentialDeliveryPrice>,
 	// ..and XCMP to communicate with the sibling chains.
 	XcmpQueue,
 )>;
 
+/// Subscribes to the XCM version of every sibling we have an HRMP channel to, once the channel is
+/// open, so that messages to it are wrapped in a version it understands.
+pub struct SubscribeToSiblingVersions;
+impl OnSystemEvent for SubscribeToSiblingVersions {
+	fn on_validation_data(_data: &PersistedValidationData) {
+		let Some(state) = ParachainSystem::relevant_messaging_state() else { return };
+		for (para, _) in &state.egress_channels {
+			let sibling = MultiLocation::new(1, X1(Parachain((*para).into())));
+			// Fails once subscribed, or if the subscription cannot be sent yet, in which case it
+			// is retried in the next block.
+			let _ = PolkadotXcm::request_version_notify(sibling);
+		}
+		let checked = state.egress_channels.len() as u64;
+		System::register_extra_weight_unchecked(
+			RocksDbWeight::get().reads(checked),
+			DispatchClass::Mandatory,
+		);
+	}
+
+	fn on_validation_code_applied() {}
+}
+
+/// The origins of the messages queued in `MessageQueue`.
+pub use pallet_parachain_inbound_xcm::AggregateMessageOrigin;
+
+impl pallet_parachain_inbound_xcm::Config for Runtime {
+	type MessageProcessor =
+		ProcessXcmMessage<AggregateMessageOrigin, XcmExecutor<XcmConfig>, RuntimeCall>;
+	type MessageQueue = MessageQueue;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type ParentServiceWeight = ParentServiceWeight;
+	type SiblingServiceWeight = SiblingServiceWeight;
+	type SiblingQueueCapacity = ConstU64<1_000>;
+	type EnqueueWeight = MessageQueueEnqueueWeight;
+	type OnGenesis = SetXcmpMaxIndividualWeight;
+}
+
 #[cfg(feature = "runtime-benchmarks")]
 parameter_types! {
 	pub ReachableDest: Option<MultiLocation> = Some(Parent.into());
@@ -158,14 +564,14 @@ parameter_types! {
 impl pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
-	type XcmRouter = XcmRouter;
+	type XcmRouter = ChargeSender<XcmRouter>;
 	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Nothing;
 	// ^ Disable dispatchable execute on the XCM pallet.
 	// Needs to be `Everything` for local testing.
 	type XcmExecutor = XcmExecutor<XcmConfig>;
-	type XcmTeleportFilter = Everything;
-	type XcmReserveTransferFilter = Nothing;
+	type XcmTeleportFilter = OnlyRelayTokenTeleports;
+	type XcmReserveTransferFilter = Everything;
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
 	type UniversalLocation = UniversalLocation;
 	type RuntimeOrigin = RuntimeOrigin;
@@ -182,7 +588,7 @@ impl pallet_xcm::Config for Runtime {
 	type WeightInfo = pallet_xcm::TestWeightInfo;
 	#[cfg(feature = "runtime-benchmarks")]
 	type ReachableDest = ReachableDest;
-	type AdminOrigin = EnsureRoot<AccountId>;
+	type AdminOrigin = GeneralAdminOrigin;
 	type MaxRemoteLockConsumers = ConstU32<0>;
 	type RemoteLockConsumerIdentifier = ();
 }
@@ -191,3 +597,27 @@ impl cumulus_pallet_xcm::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::DecodeAll;
+
+	#[test]
+	fn outbound_channel_details_mirror_xcmp_queue() {
+		let para = ParaId::from(2000);
+		let channels = [
+			cumulus_pallet_xcmp_queue::OutboundChannelDetails::new(para),
+			cumulus_pallet_xcmp_queue::OutboundChannelDetails::new(para)
+				.with_signals()
+				.with_suspended_state(),
+		];
+		for channel in channels {
+			let encoded = channel.encode();
+			let mirror = OutboundChannelDetails::decode_all(&mut &encoded[..]).unwrap();
+			assert_eq!(mirror.recipient, para);
+			assert_eq!((mirror.first_index, mirror.last_index), (0, 0));
+			assert_eq!(mirror.encode(), encoded);
+		}
+	}
+}
@@ -0,0 +1,844 @@
+//! Exercises `XcmConfig` on a simulated network: a mock relay chain and two parachains running
+//! `parachain_template_runtime`.
+//!
+//! The parachains route their messages through `ParachainSystem` and `XcmpQueue`, as they would on
+//! a live network, so [`deliver_outbound`] plays the part of the collators and the relay chain and
+//! delivers what they queued. Messages sent by the relay chain are delivered by the simulator.
+//! Either way, the parachains queue what they receive in `MessageQueue`, which
+//! [`deliver_outbound`] has execute it.
+
+mod relay_chain;
+
+use codec::{Decode, Encode};
+use cumulus_primitives_core::{
+	AbridgedHrmpChannel, ParaId, PersistedValidationData, XcmpMessageSource,
+};
+use cumulus_primitives_parachain_inherent::ParachainInherentData;
+use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
+use frame_support::{
+	assert_noop, assert_ok, storage_alias,
+	traits::{
+		EnqueueMessage, EnsureOriginWithArg, Hooks, OnQueueChanged, PalletInfoAccess,
+		ProcessMessage, ProcessMessageError, ServiceQueues,
+	},
+	weights::{Weight, WeightMeter},
+};
+use parachain_template_runtime::{
+	xcm_config::{AggregateMessageOrigin, AssetTransactors, ForeignCreators, XcmRouter},
+	AccountId, Assets, AuraId, Balance, Balances, DmpQueue, ForeignAssets, InboundXcm,
+	MessageQueue, MessageQueueServiceWeight, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall,
+	RuntimeEvent, RuntimeGenesisConfig, RuntimeOrigin, SessionKeys, System, TemplatePallet,
+	TreasuryAccount, XcmFees, XcmpQueue, UNIT,
+};
+use polkadot_parachain::primitives::{HrmpChannelId, Sibling, XcmpMessageFormat};
+use sp_runtime::{
+	traits::{AccountIdConversion, Dispatchable, Get},
+	AccountId32, BuildStorage,
+};
+use xcm::{
+	latest::prelude::*, GetVersion, VersionedMultiAssets, VersionedMultiLocation, VersionedXcm,
+};
+use xcm_executor::traits::TransactAsset;
+use xcm_simulator::{
+	decl_test_network, decl_test_parachain, decl_test_relay_chain, TestExt, XcmpMessageHandlerT,
+};
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+pub const INITIAL_BALANCE: Balance = 1_000 * UNIT;
+
+/// Stands in for Asset Hub, which `XcmConfig` trusts as the reserve of its assets.
+pub const PARA_A_ID: u32 = 1000;
+pub const PARA_B_ID: u32 = 2000;
+
+decl_test_parachain! {
+	pub struct ParaA {
+		Runtime = Runtime,
+		XcmpMessageHandler = XcmpQueue,
+		DmpMessageHandler = DmpQueue,
+		new_ext = para_ext(PARA_A_ID),
+	}
+}
+
+decl_test_parachain! {
+	pub struct ParaB {
+		Runtime = Runtime,
+		XcmpMessageHandler = XcmpQueue,
+		DmpMessageHandler = DmpQueue,
+		new_ext = para_ext(PARA_B_ID),
+	}
+}
+
+decl_test_relay_chain! {
+	pub struct Relay {
+		Runtime = relay_chain::Runtime,
+		RuntimeCall = relay_chain::RuntimeCall,
+		RuntimeEvent = relay_chain::RuntimeEvent,
+		XcmConfig = relay_chain::XcmConfig,
+		MessageQueue = relay_chain::MessageQueue,
+		System = relay_chain::System,
+		new_ext = relay_ext(),
+	}
+}
+
+decl_test_network! {
+	pub struct MockNet {
+		relay_chain = Relay,
+		parachains = vec![
+			(1000, ParaA),
+			(2000, ParaB),
+		],
+	}
+}
+
+/// The upward messages a parachain queued for the relay chain, drained by collators in
+/// `on_finalize` on a live network.
+#[storage_alias]
+type PendingUpwardMessages = StorageValue<ParachainSystem, Vec<Vec<u8>>, ValueQuery>;
+
+/// The account of a sibling parachain on the parachains.
+pub fn sibling_account_id(para: u32) -> AccountId {
+	Sibling::from(para).into_account_truncating()
+}
+
+/// The account of a parachain on the relay chain.
+pub fn child_account_id(para: u32) -> relay_chain::AccountId {
+	ParaId::from(para).into_account_truncating()
+}
+
+pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
+	let sibling = if para_id == PARA_A_ID { PARA_B_ID } else { PARA_A_ID };
+	let collator_key = AuraId::from(sp_core::sr25519::Public::from_raw([1u8; 32]));
+	let t = RuntimeGenesisConfig {
+		balances: parachain_template_runtime::BalancesConfig {
+			balances: vec![
+				(ALICE, INITIAL_BALANCE),
+				(BOB, INITIAL_BALANCE),
+				(sibling_account_id(sibling), INITIAL_BALANCE),
+			],
+		},
+		parachain_info: parachain_template_runtime::ParachainInfoConfig {
+			parachain_id: para_id.into(),
+			..Default::default()
+		},
+		collator_selection: parachain_template_runtime::CollatorSelectionConfig {
+			invulnerables: vec![ALICE],
+			..Default::default()
+		},
+		// `AuraExt` refuses to start without Aura authorities, which come from the session keys.
+		session: parachain_template_runtime::SessionConfig {
+			keys: vec![(ALICE, ALICE, SessionKeys { aura: collator_key })],
+		},
+		polkadot_xcm: parachain_template_runtime::PolkadotXcmConfig {
+			safe_xcm_version: Some(XCM_VERSION),
+			..Default::default()
+		},
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		set_validation_data(para_id, 0);
+	});
+	ext
+}
+
+/// Provides the validation data a collator would, with a channel open to every other parachain
+/// of the network so that `XcmpQueue` can send to them, each with `queued` messages in it.
+fn set_validation_data(para_id: u32, queued: u32) {
+	let mut sproof = RelayStateSproofBuilder { para_id: para_id.into(), ..Default::default() };
+	sproof.host_config.max_upward_message_size = 64 * 1024;
+	let siblings: Vec<ParaId> = [PARA_A_ID, PARA_B_ID]
+		.into_iter()
+		.filter(|id| *id != para_id)
+		.map(Into::into)
+		.collect();
+	for sibling in &siblings {
+		sproof.hrmp_channels.insert(
+			HrmpChannelId { sender: para_id.into(), recipient: *sibling },
+			AbridgedHrmpChannel {
+				max_capacity: 100,
+				max_total_size: 1024 * 1024,
+				max_message_size: 64 * 1024,
+				msg_count: queued,
+				total_size: 0,
+				mqc_head: None,
+			},
+		);
+	}
+	sproof.hrmp_egress_channel_index = Some(siblings);
+
+	let (relay_parent_storage_root, relay_chain_state) = sproof.into_state_root_and_proof();
+	let data = ParachainInherentData {
+		validation_data: PersistedValidationData {
+			parent_head: Default::default(),
+			relay_parent_number: System::block_number(),
+			relay_parent_storage_root,
+			max_pov_size: 5 * 1024 * 1024,
+		},
+		relay_chain_state,
+		downward_messages: Default::default(),
+		horizontal_messages: Default::default(),
+	};
+	assert_ok!(ParachainSystem::set_validation_data(RuntimeOrigin::none(), data));
+}
+
+/// Moves the parachain to its next block, with `queued` messages in each of its channels.
+fn next_block(para_id: u32, queued: u32) {
+	let number = System::block_number();
+	ParachainSystem::on_finalize(number);
+	System::set_block_number(number + 1);
+	ParachainSystem::on_initialize(number + 1);
+	set_validation_data(para_id, queued);
+}
+
+pub fn relay_ext() -> sp_io::TestExternalities {
+	use relay_chain::{Runtime, System};
+
+	let mut t = frame_system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> { balances: vec![(ALICE, INITIAL_BALANCE)] }
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Takes the upward and horizontal messages queued by the parachain `P`.
+fn take_outbound<P: TestExt>() -> (Vec<Vec<u8>>, Vec<(ParaId, Vec<u8>)>) {
+	P::execute_without_dispatch(|| {
+		(PendingUpwardMessages::take(), XcmpQueue::take_outbound_messages(usize::MAX))
+	})
+}
+
+/// Executes the messages the parachain `P` received, as `MessageQueue` does at the start of a block.
+fn service_queues<P: TestExt>() {
+	P::execute_without_dispatch(|| {
+		MessageQueue::service_queues(Weight::MAX);
+	});
+}
+
+/// Executes the messages the parachains received and delivers the messages they queued for the
+/// relay chain and each other, until none are left.
+pub fn deliver_outbound() {
+	loop {
+		service_queues::<ParaA>();
+		service_queues::<ParaB>();
+		let outbound =
+			[(PARA_A_ID, take_outbound::<ParaA>()), (PARA_B_ID, take_outbound::<ParaB>())];
+		if outbound
+			.iter()
+			.all(|(_, (upward, horizontal))| upward.is_empty() && horizontal.is_empty())
+		{
+			return
+		}
+
+		for (sender, (upward, horizontal)) in outbound {
+			for message in upward {
+				let mut id = [0u8; 32];
+				let _ = Relay::process_message(
+					&message,
+					sender.into(),
+					&mut WeightMeter::max_limit(),
+					&mut id,
+				);
+			}
+			for (recipient, page) in horizontal {
+				let messages = vec![(sender.into(), 1, &page[..])];
+				match u32::from(recipient) {
+					PARA_A_ID => ParaA::handle_xcmp_messages(messages.into_iter(), Weight::MAX),
+					PARA_B_ID => ParaB::handle_xcmp_messages(messages.into_iter(), Weight::MAX),
+					other => panic!("no parachain {} in the network", other),
+				};
+			}
+		}
+	}
+}
+
+fn para_events() -> Vec<RuntimeEvent> {
+	System::events().into_iter().map(|record| record.event).collect()
+}
+
+/// The location of asset `id` of `Assets` on the parachain `para`, seen from its sibling.
+fn sibling_asset(para: u32, id: u32) -> MultiLocation {
+	MultiLocation::new(
+		1,
+		X3(
+			Parachain(para),
+			PalletInstance(<Assets as PalletInfoAccess>::index() as u8),
+			GeneralIndex(id.into()),
+		),
+	)
+}
+
+fn account(who: AccountId32) -> MultiLocation {
+	AccountId32 { network: None, id: who.into() }.into()
+}
+
+/// The amount of the relay chain token that `fees` consist of.
+fn relay_token(fees: &MultiAssets) -> Balance {
+	match fees.inner() {
+		[MultiAsset { id: Concrete(location), fun: Fungible(amount) }]
+			if *location == Parent.into() =>
+			*amount,
+		other => panic!("expected fees in the relay chain token, got {:?}", other),
+	}
+}
+
+/// A message from parachain A to parachain B, paying for its execution with the relay chain token
+/// held by A's sovereign account on B.
+fn paid_message_to_b(instructions: Vec<Instruction<()>>) -> Xcm<()> {
+	let fees: MultiAsset = (Parent, UNIT).into();
+	let mut message =
+		vec![WithdrawAsset(fees.clone().into()), BuyExecution { fees, weight_limit: Unlimited }];
+	message.extend(instructions);
+	Xcm(message)
+}
+
+#[test]
+fn dmp_reserve_transfer_from_relay() {
+	MockNet::reset();
+
+	let amount = 10 * UNIT;
+	Relay::execute_with(|| {
+		assert_ok!(relay_chain::XcmPallet::limited_reserve_transfer_assets(
+			relay_chain::RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedMultiLocation::V3(Parachain(PARA_B_ID).into())),
+			Box::new(VersionedMultiLocation::V3(account(BOB))),
+			Box::new(VersionedMultiAssets::V3((Here, amount).into())),
+			0,
+			Unlimited,
+		));
+		assert_eq!(relay_chain::Balances::free_balance(child_account_id(PARA_B_ID)), amount);
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		let received = Balances::free_balance(BOB) - INITIAL_BALANCE;
+		// Execution on B is paid out of the transferred amount.
+		assert!(received > 0 && received < amount);
+	});
+}
+
+#[test]
+fn ump_transact_dispatches_as_sovereign_account() {
+	MockNet::reset();
+
+	let remark = relay_chain::RuntimeCall::System(frame_system::Call::remark_with_event {
+		remark: vec![1, 2, 3],
+	});
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			Parent.into(),
+			Xcm(vec![Transact {
+				origin_kind: OriginKind::SovereignAccount,
+				require_weight_at_most: Weight::from_parts(1_000_000_000, 1024 * 1024),
+				call: remark.encode().into(),
+			}]),
+		));
+	});
+	deliver_outbound();
+
+	Relay::execute_with(|| {
+		assert!(relay_chain::System::events().iter().any(|record| matches!(
+			&record.event,
+			relay_chain::RuntimeEvent::System(frame_system::Event::Remarked { sender, .. })
+				if *sender == child_account_id(PARA_A_ID)
+		)));
+	});
+}
+
+#[test]
+fn hrmp_transact_dispatches_through_origin_converter() {
+	MockNet::reset();
+
+	let call = RuntimeCall::TemplatePallet(pallet_parachain_template::Call::do_something {
+		something: 42,
+	});
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			MultiLocation::new(1, X1(Parachain(PARA_B_ID))),
+			paid_message_to_b(vec![
+				Transact {
+					origin_kind: OriginKind::SovereignAccount,
+					require_weight_at_most: Weight::from_parts(1_000_000_000, 200_000),
+					call: call.encode().into(),
+				},
+				RefundSurplus,
+				DepositAsset {
+					assets: All.into(),
+					beneficiary: MultiLocation::new(1, X1(Parachain(PARA_A_ID))),
+				},
+			]),
+		));
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		assert_eq!(TemplatePallet::something().map(|record| record.value), Some(42));
+		assert_eq!(
+			pallet_parachain_template::AccountValues::<Runtime>::get(sibling_account_id(PARA_A_ID))
+				.map(|entry| entry.value),
+			Some(42)
+		);
+	});
+}
+
+#[test]
+fn messages_of_congested_sibling_wait_in_xcmp_queue() {
+	MockNet::reset();
+	deliver_outbound();
+
+	let sibling_a = AggregateMessageOrigin::Sibling(PARA_A_ID.into());
+	let call = RuntimeCall::TemplatePallet(pallet_parachain_template::Call::do_something {
+		something: 42,
+	});
+	ParaB::execute_with(|| InboundXcm::on_queue_changed(sibling_a, 1_000, 0));
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			MultiLocation::new(1, X1(Parachain(PARA_B_ID))),
+			paid_message_to_b(vec![
+				Transact {
+					origin_kind: OriginKind::SovereignAccount,
+					require_weight_at_most: Weight::from_parts(1_000_000_000, 200_000),
+					call: call.encode().into(),
+				},
+				RefundSurplus,
+				DepositAsset {
+					assets: All.into(),
+					beneficiary: MultiLocation::new(1, X1(Parachain(PARA_A_ID))),
+				},
+			]),
+		));
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		assert_eq!(MessageQueue::footprint(sibling_a).count, 0);
+		assert!(TemplatePallet::something().is_none());
+
+		// `XcmpQueue` offers the message again once the queue of A has room for it.
+		InboundXcm::on_queue_changed(sibling_a, 999, 0);
+		XcmpQueue::on_idle(System::block_number(), Weight::MAX);
+		assert_eq!(MessageQueue::footprint(sibling_a).count, 1);
+	});
+	service_queues::<ParaB>();
+
+	ParaB::execute_with(|| {
+		assert_eq!(TemplatePallet::something().map(|record| record.value), Some(42));
+	});
+}
+
+#[test]
+fn siblings_cannot_crowd_out_the_relay_chain() {
+	MockNet::reset();
+
+	let sibling_a = AggregateMessageOrigin::Sibling(PARA_A_ID.into());
+	ParaB::execute_with(|| {
+		// More than the whole service weight of a block, at two instructions each.
+		let message = VersionedXcm::V3(paid_message_to_b(vec![])).encode();
+		for _ in 0..100 {
+			MessageQueue::enqueue_message(message.as_slice().try_into().unwrap(), sibling_a);
+		}
+		let message = VersionedXcm::V3(Xcm::<()>(vec![
+			UnpaidExecution { weight_limit: Unlimited, check_origin: None },
+			ClearOrigin,
+		]))
+		.encode();
+		MessageQueue::enqueue_message(
+			message.as_slice().try_into().unwrap(),
+			AggregateMessageOrigin::Parent,
+		);
+
+		MessageQueue::service_queues(MessageQueueServiceWeight::get());
+		assert_eq!(MessageQueue::footprint(AggregateMessageOrigin::Parent).count, 0);
+		assert!(MessageQueue::footprint(sibling_a).count > 0);
+	});
+}
+
+#[test]
+fn hrmp_reserve_transfer_of_asset_hub_asset() {
+	MockNet::reset();
+
+	let amount = 10 * UNIT;
+	let location = sibling_asset(PARA_A_ID, 1);
+
+	ParaB::execute_with(|| {
+		assert_ok!(ForeignAssets::force_create(
+			RuntimeOrigin::root(),
+			location,
+			ALICE.into(),
+			true,
+			1,
+		));
+		assert_ok!(XcmFees::set_units_per_second(
+			RuntimeOrigin::root(),
+			Box::new(VersionedMultiLocation::V3(location)),
+			Some(UNIT),
+		));
+	});
+
+	ParaA::execute_with(|| {
+		assert_ok!(Assets::force_create(RuntimeOrigin::root(), 1.into(), ALICE.into(), true, 1));
+		assert_ok!(Assets::mint(RuntimeOrigin::signed(ALICE), 1.into(), ALICE.into(), amount));
+		let asset = MultiLocation::new(
+			0,
+			X2(PalletInstance(<Assets as PalletInfoAccess>::index() as u8), GeneralIndex(1)),
+		);
+		let treasury = Balances::free_balance(TreasuryAccount::get());
+		assert_ok!(PolkadotXcm::limited_reserve_transfer_assets(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedMultiLocation::V3(MultiLocation::new(1, X1(Parachain(PARA_B_ID))))),
+			Box::new(VersionedMultiLocation::V3(account(BOB))),
+			Box::new(VersionedMultiAssets::V3((asset, amount).into())),
+			0,
+			Unlimited,
+		));
+		// A is the reserve, so it keeps the asset in B's sovereign account.
+		assert_eq!(Assets::balance(1, sibling_account_id(PARA_B_ID)), amount);
+		// Alice pays for the delivery of the transfer to B, to the treasury.
+		let fee = Balances::free_balance(TreasuryAccount::get()) - treasury;
+		assert!(fee > 0);
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - fee);
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		let received = ForeignAssets::balance(location, BOB);
+		assert!(received > 0 && received < amount);
+		// Execution is paid for in the asset itself, at the rate set in `XcmFees`.
+		assert_eq!(ForeignAssets::balance(location, TreasuryAccount::get()), amount - received);
+	});
+}
+
+#[test]
+fn foreign_assets_cannot_take_over_the_relay_token() {
+	MockNet::reset();
+
+	let relay = MultiLocation::parent();
+	let xcm_origin = |location| RuntimeOrigin::from(pallet_xcm::Origin::Xcm(location));
+
+	ParaB::execute_with(|| {
+		// Neither the relay chain token nor sibling assets can be created by the relay chain.
+		assert!(ForeignCreators::try_origin(xcm_origin(relay), &relay).is_err());
+		assert!(
+			ForeignCreators::try_origin(xcm_origin(relay), &sibling_asset(PARA_A_ID, 1)).is_err()
+		);
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_A_ID)));
+		assert!(
+			ForeignCreators::try_origin(xcm_origin(sibling), &sibling_asset(PARA_A_ID, 1)).is_ok()
+		);
+
+		// Even once registered in `ForeignAssets`, the relay chain token is paid out in `Balances`.
+		assert_ok!(ForeignAssets::force_create(
+			RuntimeOrigin::root(),
+			relay,
+			ALICE.into(),
+			true,
+			1,
+		));
+		let before = Balances::free_balance(BOB);
+		assert_ok!(AssetTransactors::deposit_asset(
+			&(relay, UNIT).into(),
+			&account(BOB),
+			&XcmContext::with_message_id([0; 32]),
+		));
+		assert_eq!(Balances::free_balance(BOB), before + UNIT);
+		assert_eq!(ForeignAssets::balance(relay, BOB), 0);
+	});
+}
+
+#[test]
+fn barrier_rejects_unpaid_messages() {
+	MockNet::reset();
+
+	let call = RuntimeCall::TemplatePallet(pallet_parachain_template::Call::do_something {
+		something: 42,
+	});
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			MultiLocation::new(1, X1(Parachain(PARA_B_ID))),
+			Xcm(vec![Transact {
+				origin_kind: OriginKind::SovereignAccount,
+				require_weight_at_most: Weight::from_parts(1_000_000_000, 200_000),
+				call: call.encode().into(),
+			}]),
+		));
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		assert!(para_events().iter().any(|event| matches!(
+			event,
+			RuntimeEvent::MessageQueue(pallet_message_queue::Event::ProcessingFailed {
+				origin: AggregateMessageOrigin::Sibling(sender),
+				error: ProcessMessageError::Unsupported,
+				..
+			}) if u32::from(*sender) == PARA_A_ID
+		)));
+		assert_eq!(TemplatePallet::something(), None);
+	});
+}
+
+#[test]
+fn unspent_assets_are_trapped() {
+	MockNet::reset();
+
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			MultiLocation::new(1, X1(Parachain(PARA_B_ID))),
+			paid_message_to_b(vec![]),
+		));
+	});
+	deliver_outbound();
+
+	ParaB::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_A_ID)));
+		assert!(para_events().iter().any(|event| matches!(
+			event,
+			RuntimeEvent::PolkadotXcm(pallet_xcm::Event::AssetsTrapped(_, origin, _))
+				if *origin == sibling
+		)));
+		assert!(Balances::free_balance(sibling_account_id(PARA_A_ID)) < INITIAL_BALANCE);
+	});
+}
+
+#[test]
+fn siblings_subscribe_to_each_others_version() {
+	MockNet::reset();
+
+	// The subscriptions are sent as soon as the channels are known, in the first block.
+	deliver_outbound();
+
+	ParaA::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+		assert_eq!(PolkadotXcm::get_version_for(&sibling), Some(XCM_VERSION));
+	});
+	ParaB::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_A_ID)));
+		assert_eq!(PolkadotXcm::get_version_for(&sibling), Some(XCM_VERSION));
+	});
+}
+
+#[test]
+fn messages_to_v2_sibling_are_downgraded() {
+	MockNet::reset();
+	deliver_outbound();
+
+	let call = RuntimeCall::TemplatePallet(pallet_parachain_template::Call::do_something {
+		something: 42,
+	});
+	ParaA::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+		assert_ok!(PolkadotXcm::force_xcm_version(RuntimeOrigin::root(), Box::new(sibling), 2));
+		assert_ok!(send_xcm::<XcmRouter>(
+			sibling,
+			paid_message_to_b(vec![
+				Transact {
+					origin_kind: OriginKind::SovereignAccount,
+					require_weight_at_most: Weight::from_parts(1_000_000_000, 64 * 1024),
+					call: call.encode().into(),
+				},
+				RefundSurplus,
+				// XCM v2 needs to know how many assets to deposit.
+				DepositAsset {
+					assets: AllCounted(1).into(),
+					beneficiary: MultiLocation::new(1, X1(Parachain(PARA_A_ID))),
+				},
+			]),
+		));
+	});
+
+	let (_, horizontal) = take_outbound::<ParaA>();
+	let [(recipient, page)] = &horizontal[..] else { panic!("expected one page to B") };
+	assert_eq!(u32::from(*recipient), PARA_B_ID);
+	let mut data = &page[..];
+	assert_eq!(
+		XcmpMessageFormat::decode(&mut data),
+		Ok(XcmpMessageFormat::ConcatenatedVersionedXcm)
+	);
+	assert!(matches!(VersionedXcm::<()>::decode(&mut data), Ok(VersionedXcm::V2(_))));
+	assert!(data.is_empty());
+
+	// B still understands the message, rather than it being dropped.
+	ParaB::handle_xcmp_messages(vec![(PARA_A_ID.into(), 1, &page[..])].into_iter(), Weight::MAX);
+	service_queues::<ParaB>();
+	ParaB::execute_with(|| {
+		assert_eq!(TemplatePallet::something().map(|record| record.value), Some(42));
+	});
+}
+
+#[test]
+fn delivery_fees_grow_with_the_destination_queue() {
+	MockNet::reset();
+
+	ParaA::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+		let quote =
+			|| relay_token(&validate_send::<XcmRouter>(sibling, Xcm(vec![ClearOrigin])).unwrap().1);
+		let empty = quote();
+		assert!(empty > 0);
+
+		next_block(PARA_A_ID, 10);
+		// 5% more for each of the 10 queued messages: 1.05^10 ~ 1.629.
+		let queued = quote();
+		assert!(queued > empty * 162 / 100 && queued < empty * 163 / 100);
+	});
+}
+
+#[test]
+fn delivery_fees_grow_with_the_pages_held_for_the_destination() {
+	MockNet::reset();
+
+	let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+	let quote =
+		|| relay_token(&validate_send::<XcmRouter>(sibling, Xcm(vec![ClearOrigin])).unwrap().1);
+
+	let empty = ParaA::execute_with(|| {
+		let empty = quote();
+		// `XcmpQueue` holds the page until the collator hands it over to the relay chain.
+		assert_ok!(send_xcm::<XcmRouter>(sibling, Xcm(vec![ClearOrigin])));
+		let held = quote();
+		assert!(held > empty * 104 / 100 && held <= empty * 105 / 100);
+		empty
+	});
+
+	take_outbound::<ParaA>();
+	ParaA::execute_with(|| assert_eq!(quote(), empty));
+}
+
+#[test]
+fn delivery_fees_count_every_page_held_for_the_destination() {
+	MockNet::reset();
+
+	ParaA::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+		let quote =
+			|| relay_token(&validate_send::<XcmRouter>(sibling, Xcm(vec![ClearOrigin])).unwrap().1);
+		let empty = quote();
+
+		// Each message takes more than half of a page, so every one of them starts a new page.
+		let large = Xcm(vec![Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::zero(),
+			call: vec![0u8; 40 * 1024].into(),
+		}]);
+		for _ in 0..3 {
+			assert_ok!(send_xcm::<XcmRouter>(sibling, large.clone()));
+		}
+		// 5% more for each of the 3 pages: 1.05^3 ~ 1.158.
+		let held = quote();
+		assert!(held > empty * 115 / 100 && held < empty * 116 / 100);
+	});
+}
+
+#[test]
+fn delivery_fees_grow_with_the_upward_messages_held() {
+	MockNet::reset();
+
+	let quote = || {
+		relay_token(
+			&validate_send::<XcmRouter>(MultiLocation::parent(), Xcm(vec![ClearOrigin]))
+				.unwrap()
+				.1,
+		)
+	};
+
+	let empty = ParaA::execute_with(|| {
+		let empty = quote();
+		// `ParachainSystem` holds the message until the collator hands it over to the relay chain.
+		assert_ok!(send_xcm::<XcmRouter>(MultiLocation::parent(), Xcm(vec![ClearOrigin])));
+		assert_eq!(PendingUpwardMessages::decode_len(), Some(1));
+		let held = quote();
+		assert!(held > empty * 104 / 100 && held <= empty * 105 / 100);
+		empty
+	});
+
+	take_outbound::<ParaA>();
+	ParaA::execute_with(|| assert_eq!(quote(), empty));
+}
+
+#[test]
+fn pallet_xcm_send_charges_the_sender() {
+	MockNet::reset();
+
+	ParaA::execute_with(|| {
+		let sibling = MultiLocation::new(1, X1(Parachain(PARA_B_ID)));
+		let message = Xcm(vec![ClearOrigin]);
+		// `pallet_xcm` sends the message from Alice's account.
+		let mut sent = message.clone();
+		sent.0.insert(0, DescendOrigin(account(ALICE).interior));
+		let fee = relay_token(&validate_send::<XcmRouter>(sibling, sent).unwrap().1);
+		let treasury = Balances::free_balance(TreasuryAccount::get());
+
+		assert_ok!(PolkadotXcm::send(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedMultiLocation::V3(sibling)),
+			Box::new(VersionedXcm::V3(message)),
+		));
+		assert_eq!(Balances::free_balance(ALICE), INITIAL_BALANCE - fee);
+		assert_eq!(Balances::free_balance(TreasuryAccount::get()), treasury + fee);
+	});
+}
+
+#[test]
+fn governance_executes_overweight_messages() {
+	MockNet::reset();
+	deliver_outbound();
+
+	ParaA::execute_with(|| {
+		assert_ok!(send_xcm::<XcmRouter>(
+			MultiLocation::new(1, X1(Parachain(PARA_B_ID))),
+			paid_message_to_b(vec![]),
+		));
+	});
+	let (_, horizontal) = take_outbound::<ParaA>();
+	for (_, page) in &horizontal {
+		ParaB::handle_xcmp_messages(
+			vec![(PARA_A_ID.into(), 1, &page[..])].into_iter(),
+			Weight::MAX,
+		);
+	}
+
+	ParaB::execute_with(|| {
+		// Not enough for the message, which takes a unit of weight per instruction.
+		MessageQueue::service_queues(Weight::from_parts(1_500_000_000, 1024 * 1024));
+		let (page_index, message_index) = para_events()
+			.into_iter()
+			.find_map(|event| match event {
+				RuntimeEvent::MessageQueue(pallet_message_queue::Event::OverweightEnqueued {
+					page_index,
+					message_index,
+					..
+				}) => Some((page_index, message_index)),
+				_ => None,
+			})
+			.expect("the message is overweight");
+
+		// Only governance may execute it.
+		let call = RuntimeCall::MessageQueue(pallet_message_queue::Call::execute_overweight {
+			message_origin: AggregateMessageOrigin::Sibling(PARA_A_ID.into()),
+			page: page_index,
+			index: message_index,
+			weight_limit: Weight::MAX,
+		});
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(ALICE)),
+			frame_system::Error::<Runtime>::CallFiltered
+		);
+
+		let sovereign = sibling_account_id(PARA_A_ID);
+		let before = Balances::free_balance(&sovereign);
+		assert_ok!(DmpQueue::execute_overweight(
+			RuntimeOrigin::root(),
+			(AggregateMessageOrigin::Sibling(PARA_A_ID.into()), page_index, message_index),
+			Weight::MAX,
+		));
+		// The message withdrew the fees for its execution.
+		assert!(Balances::free_balance(&sovereign) < before);
+	});
+}